@@ -2,8 +2,10 @@ use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 use pprof::criterion::{Output, PProfProfiler};
 use pprof::flamegraph::Options;
 use std::str::FromStr;
+use sudoku::grader::Grade;
 use sudoku::model::board::SudokuProblem;
-use sudoku::solver::search::Search;
+use sudoku::solver::search::{Branching, Search};
+use sudoku::solver::SolverType;
 
 const EASY_SUITE: [&str; 8] = [
     "016400000200009000400000062070230100100000003003087040960000005000800007000006820",
@@ -20,39 +22,79 @@ const HARD_SUITE: [&str; 2] = [
     "004070000800000401013004000000000000120000804000009700600007002000100060000308970",
 ];
 
-pub fn dfs_benchmarks(c: &mut Criterion) {
-    let mut group = c.benchmark_group("depth-first-search");
-    let mut counter = 0;
+// Print the difficulty grade of each puzzle in the suites.
+fn label_suites() {
+    for (name, suite) in [
+        ("easy-suite", &EASY_SUITE[..]),
+        ("hard-suite", &HARD_SUITE[..]),
+    ] {
+        for sdm in suite {
+            let grade = Grade::from_problem(&SudokuProblem::from_str(sdm).unwrap());
+            println!("{name} {sdm}: {grade}");
+        }
+    }
+}
+
+pub fn solver_benchmarks(c: &mut Criterion) {
+    label_suites();
+
+    for solver_type in SolverType::all() {
+        let mut group = c.benchmark_group(solver_type.to_string());
+        let mut counter = 0;
+
+        group.bench_function("easy-suite", move |b| {
+            b.iter_batched(
+                || {
+                    let sdm = EASY_SUITE[counter];
+                    counter = (counter + 1) % EASY_SUITE.len();
+                    solver_type.build(SudokuProblem::from_str(sdm).unwrap())
+                },
+                |solver| {
+                    solver.run().unwrap();
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        counter = 0;
+        group.bench_function("hard-suite", move |b| {
+            b.iter_batched(
+                || {
+                    let sdm = HARD_SUITE[counter];
+                    counter = (counter + 1) % HARD_SUITE.len();
+                    solver_type.build(SudokuProblem::from_str(sdm).unwrap())
+                },
+                |solver| {
+                    solver.run().unwrap();
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        group.finish();
+    }
+}
+
+pub fn branching_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search-branching");
 
-    group.bench_function("easy-suite", move |b| {
-        b.iter_batched(
-            || {
-                let sdm = EASY_SUITE[counter];
-                counter = (counter + 1) % EASY_SUITE.len();
-                Search::new(SudokuProblem::from_str(sdm).unwrap())
-            },
-            |search| {
-                search.run().unwrap();
-            },
-            BatchSize::SmallInput,
-        )
-    });
+    for branching in Branching::all() {
+        let mut counter = 0;
 
-    counter = 0;
-    group.bench_function("hard-suite", move |b| {
-        b.iter_batched(
-            || {
-                let sdm = HARD_SUITE[counter];
-                counter = (counter + 1) % HARD_SUITE.len();
-                let p = SudokuProblem::from_str(sdm).unwrap();
-                Search::new(p)
-            },
-            |search| {
-                search.run().unwrap();
-            },
-            BatchSize::SmallInput,
-        )
-    });
+        group.bench_function(format!("hard-suite/{}", branching), move |b| {
+            b.iter_batched(
+                || {
+                    let sdm = HARD_SUITE[counter];
+                    counter = (counter + 1) % HARD_SUITE.len();
+                    Search::new(SudokuProblem::from_str(sdm).unwrap()).with_branching(*branching)
+                },
+                |search| {
+                    search.run().unwrap();
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
 
     group.finish();
 }
@@ -63,6 +105,6 @@ criterion_group!(
         100,
         Output::Flamegraph(Some(Options::default()))
     ));
-    targets = dfs_benchmarks
+    targets = solver_benchmarks, branching_benchmarks
 );
 criterion_main!(benches);
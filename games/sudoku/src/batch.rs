@@ -0,0 +1,228 @@
+use crate::model::board::{ParseError, SudokuProblem};
+use crate::model::format::Format;
+use crate::solver::search::SearchError;
+use crate::solver::SolverType;
+use rayon::prelude::*;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+
+// The number of puzzles read in before solving them in parallel, which bounds the memory used on large inputs.
+const CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(Box<SudokuProblem>),
+    NoSolution,
+    Invalid(ParseError),
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Solved(solution) => write!(f, "{:?}", solution),
+            Outcome::NoSolution => write!(f, "no solution"),
+            Outcome::Invalid(error) => write!(f, "invalid: {}", error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Throughput {
+    pub puzzles: usize,
+    pub solved: usize,
+    pub unsolvable: usize,
+    pub invalid: usize,
+    pub threads: usize,
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    pub fn puzzles_per_second(&self) -> f64 {
+        self.puzzles as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for Throughput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} puzzles ({} solved, {} unsolvable, {} invalid) in {:.3}s on {} threads: {:.1} puzzles/s",
+            self.puzzles,
+            self.solved,
+            self.unsolvable,
+            self.invalid,
+            self.elapsed.as_secs_f64(),
+            self.threads,
+            self.puzzles_per_second()
+        )
+    }
+}
+
+// Solve many puzzles (one per line, in the `sdm` or `line` format) across all the CPU cores.
+// Writes one outcome per puzzle, in the same order as the input; blank lines are skipped.
+#[derive(Debug)]
+pub struct Batch {
+    solver_type: SolverType,
+    // The puzzles are parsed onto its units (see `Format::parse_with`).
+    layout: SudokuProblem,
+}
+
+impl Batch {
+    pub fn new(solver_type: SolverType) -> Batch {
+        Self {
+            solver_type,
+            layout: SudokuProblem::default(),
+        }
+    }
+
+    // The jigsaw & extra regions shared by every puzzle.
+    pub fn with_layout(mut self, layout: SudokuProblem) -> Batch {
+        self.layout = layout;
+        self
+    }
+
+    pub fn run(
+        &self,
+        reader: impl BufRead,
+        writer: &mut impl Write,
+    ) -> std::io::Result<Throughput> {
+        let start = Instant::now();
+        let mut throughput = Throughput {
+            threads: rayon::current_num_threads(),
+            ..Throughput::default()
+        };
+        let mut lines = reader.lines();
+
+        loop {
+            let chunk = lines
+                .by_ref()
+                .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+                .take(CHUNK_SIZE)
+                .collect::<Result<Vec<String>, _>>()?;
+
+            if chunk.is_empty() {
+                break;
+            }
+
+            let outcomes = chunk
+                .par_iter()
+                .map(|line| self.solve(line.trim()))
+                .collect::<Vec<Outcome>>();
+
+            for outcome in outcomes {
+                throughput.puzzles += 1;
+
+                match outcome {
+                    Outcome::Solved(_) => throughput.solved += 1,
+                    Outcome::NoSolution => throughput.unsolvable += 1,
+                    Outcome::Invalid(_) => throughput.invalid += 1,
+                }
+
+                writeln!(writer, "{}", outcome)?;
+            }
+        }
+
+        writer.flush()?;
+        throughput.elapsed = start.elapsed();
+        Ok(throughput)
+    }
+
+    fn solve(&self, sdm: &str) -> Outcome {
+        match Format::Line.parse_with(sdm, &self.layout) {
+            Ok(problem) => match self.solver_type.build(problem).run() {
+                Ok(solution) => Outcome::Solved(Box::new(solution)),
+                Err(SearchError::NoSolution) => Outcome::NoSolution,
+                Err(error) => unreachable!("the batch sets no budgets, but {}", error),
+            },
+            Err(error) => Outcome::Invalid(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::variant::Variant;
+    use std::io::Cursor;
+
+    #[test]
+    fn batch() {
+        // Setup
+        let input = [
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+            "",
+            "not a puzzle",
+            "012345678900000000000000000000000000000000000000000000000000000000000000000000000",
+            "  016400000200009000400000062070230100100000003003087040960000005000800007000006820  ",
+        ]
+        .join("\n");
+        let batch = Batch::new(SolverType::DancingLinks);
+        let mut output = Vec::new();
+
+        // Execute
+        let throughput = batch.run(Cursor::new(input), &mut output).unwrap();
+
+        // Verify
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            [
+                "379526814564318972281479365435267198698143527712895436923754681146982753857631249",
+                "invalid: invalid character 'n' at position 1 (expected 0-9)",
+                "no solution",
+                "316452978285679314497318562879234156142965783653187249968721435521843697734596821",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(throughput.puzzles, 4);
+        assert_eq!(throughput.solved, 2);
+        assert_eq!(throughput.unsolvable, 1);
+        assert_eq!(throughput.invalid, 1);
+    }
+
+    #[test]
+    fn batch_order() {
+        // More puzzles than fit in a single chunk.
+        let sdms = [
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+            "012345678900000000000000000000000000000000000000000000000000000000000000000000000",
+        ];
+        let input = (0..CHUNK_SIZE + 3)
+            .map(|i| sdms[i % 2])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut output = Vec::new();
+
+        let throughput = Batch::new(SolverType::DancingLinks)
+            .run(Cursor::new(input), &mut output)
+            .unwrap();
+
+        let lines = String::from_utf8(output).unwrap();
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), CHUNK_SIZE + 3);
+        assert_eq!(throughput.puzzles, CHUNK_SIZE + 3);
+
+        for (i, line) in lines.iter().enumerate() {
+            assert_eq!(*line == "no solution", i % 2 == 1, "{}", i);
+        }
+    }
+
+    #[test]
+    fn batch_variant() {
+        // The givens repeat a 7 along the anti-diagonal.
+        let input =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let mut output = Vec::new();
+
+        let throughput = Batch::new(SolverType::DancingLinks)
+            .with_layout(SudokuProblem::default().with_variant(Variant::X).unwrap())
+            .run(Cursor::new(input), &mut output)
+            .unwrap();
+
+        assert_eq!(throughput.invalid, 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "invalid: duplicate 7 given in extra region 2 (at r2c8 and r4c6)\n"
+        );
+    }
+}
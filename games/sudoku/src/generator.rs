@@ -0,0 +1,367 @@
+use crate::grader::{Difficulty, Grade};
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::transform::{Transform, TransformError};
+use crate::model::variant::Variant;
+use crate::solver::dlx::Dlx;
+use crate::solver::Solver;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+// The fresh grids tried for a target difficulty, by default, before giving up.
+const ATTEMPTS: usize = 1000;
+
+const SYMMETRIES: [Symmetry; 3] = [Symmetry::None, Symmetry::Rotational, Symmetry::Mirror];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    Rotational,
+    Mirror,
+}
+
+impl Symmetry {
+    pub fn all() -> &'static [Symmetry] {
+        &SYMMETRIES[..]
+    }
+
+    // The cells which are cleared together with the cell at `index`, so that the clues keep the symmetry.
+    fn orbit(&self, index: usize) -> Vec<usize> {
+        let (row, column) = (index / 9, index % 9);
+        let partner = match self {
+            Symmetry::None => index,
+            Symmetry::Rotational => (9 * 9) - 1 - index,
+            Symmetry::Mirror => (row * 9) + (8 - column),
+        };
+
+        if partner == index {
+            vec![index]
+        } else {
+            vec![index, partner]
+        }
+    }
+}
+
+impl std::str::FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "none" => Ok(Symmetry::None),
+            "rotational" => Ok(Symmetry::Rotational),
+            "mirror" => Ok(Symmetry::Mirror),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Symmetry::None => write!(f, "none"),
+            Symmetry::Rotational => write!(f, "rotational"),
+            Symmetry::Mirror => write!(f, "mirror"),
+        }
+    }
+}
+
+// Generate puzzles with a unique solution, reproducibly for the same seed.
+#[derive(Debug)]
+pub struct Generator {
+    symmetry: Symmetry,
+    difficulty: Option<Difficulty>,
+    variant: Variant,
+    attempts: usize,
+    rng: StdRng,
+}
+
+impl Generator {
+    pub fn new(symmetry: Symmetry, difficulty: Option<Difficulty>, seed: u64) -> Generator {
+        Self {
+            symmetry,
+            difficulty,
+            variant: Variant::default(),
+            attempts: ATTEMPTS,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn with_variant(mut self, variant: Variant) -> Generator {
+        self.variant = variant;
+        self
+    }
+
+    // The most fresh grids to try for a target difficulty (at least 1).
+    pub fn with_attempts(mut self, attempts: usize) -> Generator {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    // When targeting a difficulty, fresh grids are tried until one grades to exactly that difficulty.
+    // `None` when none of the attempts do.
+    pub fn generate(&mut self) -> Option<SudokuProblem> {
+        for _ in 0..self.attempts {
+            let solution = self.fill();
+            let puzzle = self.remove_clues(solution);
+
+            match self.difficulty {
+                Some(difficulty) if Grade::from_problem(&puzzle).difficulty() != difficulty => {}
+                _ => return Some(puzzle),
+            }
+        }
+
+        None
+    }
+
+    // A random puzzle equivalent to the problem: its digits relabelled, its rows & columns shuffled within the bands &
+    // stacks, the bands & stacks shuffled, then maybe transposed & turned.  So it keeps the difficulty & uniqueness.
+    pub fn variant_of(&mut self, problem: &SudokuProblem) -> Result<SudokuProblem, TransformError> {
+        let mut values = SudokuValue::candidates().to_vec();
+        values.shuffle(&mut self.rng);
+        let mut relabel = [SudokuValue::Unknown; 9];
+        relabel.copy_from_slice(&values);
+        let mut transforms = vec![Transform::Relabel(relabel)];
+
+        // Two random swaps (each maybe of a line with itself) reach every order of 3 lines.
+        for _ in 0..2 {
+            for band in 0..3 {
+                let (first, second) = self.pair();
+                transforms.push(Transform::SwapRows(band * 3 + first, band * 3 + second));
+                let (first, second) = self.pair();
+                transforms.push(Transform::SwapColumns(band * 3 + first, band * 3 + second));
+            }
+
+            let (first, second) = self.pair();
+            transforms.push(Transform::SwapBands(first, second));
+            let (first, second) = self.pair();
+            transforms.push(Transform::SwapStacks(first, second));
+        }
+
+        if self.rng.gen_bool(0.5) {
+            transforms.push(Transform::Transpose);
+        }
+        transforms.push(Transform::Rotate(self.rng.gen_range(0..4)));
+
+        transforms
+            .iter()
+            .try_fold(problem.clone(), |problem, transform| {
+                transform.apply(&problem)
+            })
+    }
+
+    fn pair(&mut self) -> (usize, usize) {
+        (self.rng.gen_range(0..3), self.rng.gen_range(0..3))
+    }
+
+    // A random complete grid, via a depth first search which tries the candidates in a random order.
+    fn fill(&mut self) -> SudokuProblem {
+        let empty = SudokuProblem::default()
+            .with_variant(self.variant)
+            .expect("the empty grid has no duplicates");
+        let mut stack = vec![empty];
+
+        while let Some(current) = stack.pop() {
+            if current.is_complete() {
+                return current;
+            }
+
+            let cell = (0..9 * 9)
+                .map(|index| Cell::index(index).expect("must be a valid index"))
+                .filter(|cell| current.value(cell) == SudokuValue::Unknown)
+                .min_by_key(|cell| current.candidates(cell).count())
+                .expect("must have an unknown cell");
+            let mut values = current.candidates(&cell).collect::<Vec<_>>();
+            values.shuffle(&mut self.rng);
+
+            for value in values {
+                let next = current.replace(&cell, value);
+
+                if next.is_valid() {
+                    stack.push(next);
+                }
+            }
+        }
+
+        unreachable!("the empty grid must have a solution");
+    }
+
+    // Clear the clues in a random order, skipping any clue whose removal loses the unique solution.
+    // When targeting a difficulty (below expert), also skip any removal which makes the puzzle too hard.
+    fn remove_clues(&mut self, solution: SudokuProblem) -> SudokuProblem {
+        let mut puzzle = solution;
+        let mut indices = (0..9 * 9).collect::<Vec<usize>>();
+        indices.shuffle(&mut self.rng);
+
+        for index in indices {
+            let cell = Cell::index(index).expect("must be a valid index");
+
+            if puzzle.value(&cell) == SudokuValue::Unknown {
+                continue;
+            }
+
+            let mut candidate = puzzle.clone();
+
+            for i in self.symmetry.orbit(index) {
+                let cell = Cell::index(i).expect("must be a valid index");
+                candidate = candidate.replace(&cell, SudokuValue::Unknown);
+            }
+
+            if Dlx::new(candidate.clone()).count(2) != Ok(1) {
+                continue;
+            }
+
+            if let Some(difficulty) = self.difficulty {
+                if difficulty < Difficulty::Expert
+                    && Grade::from_problem(&candidate).difficulty() > difficulty
+                {
+                    continue;
+                }
+            }
+
+            puzzle = candidate;
+        }
+
+        puzzle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::transform::canonical;
+    use std::str::FromStr;
+
+    #[test]
+    fn symmetry_round_trip() {
+        for symmetry in Symmetry::all() {
+            assert_eq!(
+                Symmetry::from_str(&symmetry.to_string()).unwrap(),
+                *symmetry
+            );
+        }
+
+        Symmetry::from_str("radial").unwrap_err();
+    }
+
+    #[test]
+    fn orbit() {
+        assert_eq!(Symmetry::None.orbit(10), vec![10]);
+        assert_eq!(Symmetry::Rotational.orbit(10), vec![10, 70]);
+        assert_eq!(Symmetry::Rotational.orbit(40), vec![40]);
+        assert_eq!(Symmetry::Mirror.orbit(10), vec![10, 16]);
+        assert_eq!(Symmetry::Mirror.orbit(13), vec![13]);
+    }
+
+    #[test]
+    fn fill() {
+        let mut generator = Generator::new(Symmetry::None, None, 7);
+        let solution = generator.fill();
+        assert!(solution.is_valid());
+        assert!(solution.is_complete());
+    }
+
+    #[test]
+    fn generate() {
+        for symmetry in Symmetry::all() {
+            // Setup
+            let mut generator = Generator::new(*symmetry, None, 11);
+
+            // Execute
+            let puzzle = generator.generate().unwrap();
+
+            // Verify
+            assert!(puzzle.is_valid());
+            assert_eq!(Dlx::new(puzzle.clone()).count(2), Ok(1), "{}", symmetry);
+
+            for index in 0..9 * 9 {
+                let known =
+                    |i: usize| puzzle.value(&Cell::index(i).unwrap()) != SudokuValue::Unknown;
+
+                for i in symmetry.orbit(index) {
+                    assert_eq!(known(index), known(i), "{} {}", symmetry, index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generate_reproducible() {
+        let first = Generator::new(Symmetry::Rotational, None, 42)
+            .generate()
+            .unwrap();
+        let second = Generator::new(Symmetry::Rotational, None, 42)
+            .generate()
+            .unwrap();
+        let other = Generator::new(Symmetry::Rotational, None, 43)
+            .generate()
+            .unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn generate_difficulty() {
+        // Setup
+        let mut generator = Generator::new(Symmetry::Rotational, Some(Difficulty::Easy), 3);
+
+        // Execute
+        let puzzle = generator.generate().unwrap();
+
+        // Verify
+        assert_eq!(Grade::from_problem(&puzzle).difficulty(), Difficulty::Easy);
+        assert_eq!(Dlx::new(puzzle).count(2), Ok(1));
+    }
+
+    #[test]
+    fn generate_attempts() {
+        // Setup
+        // An expert target removes the clues as without one, so the only grid tried is the same.
+        let first = Generator::new(Symmetry::None, None, 1).generate().unwrap();
+        assert_ne!(Grade::from_problem(&first).difficulty(), Difficulty::Expert);
+        let mut generator =
+            Generator::new(Symmetry::None, Some(Difficulty::Expert), 1).with_attempts(1);
+
+        // Execute
+        let puzzle = generator.generate();
+
+        // Verify
+        assert_eq!(puzzle, None);
+    }
+
+    #[test]
+    fn generate_variant() {
+        for variant in Variant::all() {
+            // Setup
+            let mut generator = Generator::new(Symmetry::None, None, 5).with_variant(*variant);
+
+            // Execute
+            let puzzle = generator.generate().unwrap();
+
+            // Verify
+            assert_eq!(puzzle.regions(), variant.regions());
+            assert!(puzzle.is_valid());
+            assert_eq!(Dlx::new(puzzle).count(2), Ok(1), "{}", variant);
+        }
+    }
+
+    #[test]
+    fn variant_of() {
+        // Setup
+        let puzzle = Generator::new(Symmetry::None, None, 13).generate().unwrap();
+        let mut generator = Generator::new(Symmetry::None, None, 17);
+
+        // Execute
+        let variants = (0..5)
+            .map(|_| generator.variant_of(&puzzle).unwrap())
+            .collect::<Vec<_>>();
+
+        // Verify
+        for variant in variants.iter() {
+            assert_eq!(variant.unknowns(), puzzle.unknowns());
+            assert_eq!(Dlx::new(variant.clone()).count(2), Ok(1));
+            assert_eq!(canonical(variant), canonical(&puzzle));
+        }
+        assert!(variants.iter().any(|variant| variant != &puzzle));
+    }
+}
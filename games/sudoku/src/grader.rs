@@ -0,0 +1,264 @@
+use crate::model::board::SudokuProblem;
+use crate::solver::logical::{Logical, SolvePath, Technique};
+
+// The score of a puzzle the logical techniques cannot finish, before adding the unknowns left over.
+const GUESSING: u32 = 100;
+
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn all() -> &'static [Difficulty] {
+        &DIFFICULTIES[..]
+    }
+
+    // Easy puzzles need only singles, medium puzzles pairs & intersections, and hard puzzles the rest of the techniques.
+    // Expert puzzles require guessing.
+    pub fn from_score(score: u32) -> Self {
+        match score {
+            0..=24 => Difficulty::Easy,
+            25..=49 => Difficulty::Medium,
+            50..=99 => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            "expert" => Ok(Difficulty::Expert),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+            Difficulty::Expert => write!(f, "expert"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grade {
+    hardest: Option<Technique>,
+    steps: usize,
+    requires_guessing: bool,
+    score: u32,
+}
+
+impl Grade {
+    pub fn from_problem(problem: &SudokuProblem) -> Self {
+        Grade::from_solve_path(&Logical::new(problem.clone()).run())
+    }
+
+    // The score is the rating of the hardest technique, plus 1 for every step beyond the singles (short of `GUESSING`).
+    // When the techniques get stuck, it is instead `GUESSING` plus the number of unknowns left.
+    pub fn from_solve_path(solve_path: &SolvePath) -> Self {
+        let steps = solve_path.steps().len();
+        let hardest = solve_path.steps().iter().map(|step| step.technique).max();
+
+        if !solve_path.is_solved() {
+            return Self {
+                hardest,
+                steps,
+                requires_guessing: true,
+                score: GUESSING + solve_path.result().unknowns() as u32,
+            };
+        }
+
+        let advanced = solve_path
+            .steps()
+            .iter()
+            .filter(|step| step.technique > Technique::HiddenSingle)
+            .count() as u32;
+
+        Self {
+            hardest,
+            steps,
+            requires_guessing: false,
+            score: logical_score(hardest, advanced),
+        }
+    }
+
+    // The hardest technique applied, or `None` if no steps were needed.
+    pub fn hardest(&self) -> Option<Technique> {
+        self.hardest
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn requires_guessing(&self) -> bool {
+        self.requires_guessing
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::from_score(self.score)
+    }
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.difficulty(), self.score)?;
+
+        if self.requires_guessing {
+            write!(f, ": requires guessing after {} steps", self.steps)
+        } else {
+            match self.hardest {
+                Some(technique) => write!(f, ": {} in {} steps", technique, self.steps),
+                None => write!(f, ": already solved"),
+            }
+        }
+    }
+}
+
+// However many steps it takes, a puzzle solved without guessing never scores as an expert one.
+fn logical_score(hardest: Option<Technique>, advanced: u32) -> u32 {
+    (hardest.map_or(0, rating) + advanced).min(GUESSING - 1)
+}
+
+fn rating(technique: Technique) -> u32 {
+    match technique {
+        Technique::NakedSingle => 10,
+        Technique::HiddenSingle => 15,
+        Technique::NakedPair => 25,
+        Technique::HiddenPair => 30,
+        Technique::PointingPair => 30,
+        Technique::BoxLineReduction => 35,
+        Technique::NakedTriple => 40,
+        Technique::HiddenTriple => 45,
+        Technique::XWing => 55,
+        Technique::SimpleColoring => 60,
+        Technique::XYWing => 65,
+        Technique::Swordfish => 70,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn difficulty_round_trip() {
+        for difficulty in Difficulty::all() {
+            assert_eq!(
+                Difficulty::from_str(&difficulty.to_string()).unwrap(),
+                *difficulty
+            );
+        }
+
+        Difficulty::from_str("impossible").unwrap_err();
+    }
+
+    #[test]
+    fn ratings_follow_technique_order() {
+        for pair in Technique::all().windows(2) {
+            assert!(rating(pair[0]) <= rating(pair[1]), "{:?}", pair);
+        }
+
+        assert_eq!(
+            Difficulty::from_score(rating(Technique::HiddenSingle)),
+            Difficulty::Easy
+        );
+        assert_eq!(
+            Difficulty::from_score(rating(Technique::NakedPair)),
+            Difficulty::Medium
+        );
+        assert_eq!(
+            Difficulty::from_score(rating(Technique::XWing)),
+            Difficulty::Hard
+        );
+        assert_eq!(Difficulty::from_score(GUESSING), Difficulty::Expert);
+    }
+
+    #[test]
+    fn logical_score_below_guessing() {
+        assert_eq!(logical_score(None, 0), 0);
+        assert_eq!(logical_score(Some(Technique::XWing), 10), 65);
+        // A long path of advanced steps stays hard.
+        let score = logical_score(Some(Technique::XWing), 45);
+        assert_eq!(score, GUESSING - 1);
+        assert_eq!(Difficulty::from_score(score), Difficulty::Hard);
+        assert_eq!(
+            Difficulty::from_score(logical_score(Some(Technique::Swordfish), 200)),
+            Difficulty::Hard
+        );
+    }
+
+    #[test]
+    fn grade_easy() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+
+        // Execute
+        let grade = Grade::from_problem(&problem);
+
+        // Verify
+        assert!(!grade.requires_guessing());
+        assert!(grade.hardest().unwrap() <= Technique::HiddenSingle);
+        assert_eq!(grade.steps(), problem.unknowns());
+        assert_eq!(grade.difficulty(), Difficulty::Easy);
+    }
+
+    #[test]
+    fn grade_expert() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        )
+        .unwrap();
+
+        // Execute
+        let grade = Grade::from_problem(&problem);
+
+        // Verify
+        assert!(grade.requires_guessing());
+        assert_eq!(grade.score(), GUESSING + problem.unknowns() as u32);
+        assert_eq!(grade.difficulty(), Difficulty::Expert);
+    }
+
+    #[test]
+    fn grade_solved() {
+        let problem = SudokuProblem::from_str(
+            "379526814564318972281479365435267198698143527712895436923754681146982753857631249",
+        )
+        .unwrap();
+        let grade = Grade::from_problem(&problem);
+        assert_eq!(grade.hardest(), None);
+        assert_eq!(grade.score(), 0);
+        assert_eq!(grade.difficulty(), Difficulty::Easy);
+        assert_eq!(grade.to_string(), "easy (0): already solved");
+    }
+}
@@ -1,4 +1,8 @@
+pub mod batch;
+pub mod generator;
+pub mod grader;
 pub mod model;
+pub mod play;
 pub mod solver;
 
 #[cfg(test)]
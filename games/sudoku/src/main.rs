@@ -1,38 +1,767 @@
-use blarg::{CommandLineParser, GeneralParser, Parameter, Scalar};
+use blarg::{
+    prelude::*, Collection, CommandLineParser, Condition, GeneralParser, Nargs, Optional,
+    Parameter, Scalar, Switch,
+};
+use rand::{thread_rng, Rng};
+use simurgh_core::player::PlayerType;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+use sudoku::batch::Batch;
+use sudoku::generator::{Generator, Symmetry};
+use sudoku::grader::{Difficulty, Grade};
 use sudoku::model::board::SudokuProblem;
-use sudoku::solver::search::Search;
+use sudoku::model::constraint::Constraint;
+use sudoku::model::format::Format;
+use sudoku::model::jigsaw::Jigsaw;
+use sudoku::model::killer::{Cages, KillerProblem};
+use sudoku::model::relation::{RelationProblem, Relations};
+use sudoku::model::render::Renderer;
+use sudoku::model::samurai::SamuraiProblem;
+use sudoku::model::sized::SizedProblem;
+use sudoku::model::variant::Variant;
+use sudoku::play::Game;
+use sudoku::solver::logical::Logical;
+use sudoku::solver::samurai::Samurai;
+use sudoku::solver::search::{Branching, Search, TieBreak};
+use sudoku::solver::{SolverType, Uniqueness};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Solve,
+    Check,
+    Explain,
+    Grade,
+    Generate,
+    Batch,
+    Play,
+}
+
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Solve => write!(f, "solve"),
+            Command::Check => write!(f, "check"),
+            Command::Explain => write!(f, "explain"),
+            Command::Grade => write!(f, "grade"),
+            Command::Generate => write!(f, "generate"),
+            Command::Batch => write!(f, "batch"),
+            Command::Play => write!(f, "play"),
+        }
+    }
+}
+
+impl std::str::FromStr for Command {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "solve" => Ok(Command::Solve),
+            "check" => Ok(Command::Check),
+            "explain" => Ok(Command::Explain),
+            "grade" => Ok(Command::Grade),
+            "generate" => Ok(Command::Generate),
+            "batch" => Ok(Command::Batch),
+            "play" => Ok(Command::Play),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
 
 #[derive(Debug)]
 struct Parameters {
-    problem: SudokuProblem,
+    command: Command,
+    solver: SolverType,
+    variant: Variant,
+    jigsaw: Option<String>,
+    constraints: Vec<Constraint>,
+    input_format: Option<Format>,
+    output_format: Option<Format>,
+    colour: bool,
+    problem: String,
+    limit: usize,
+    box_size: usize,
+    cages: Option<String>,
+    relations: Option<String>,
+    samurai: bool,
+    symmetry: Symmetry,
+    difficulty: Option<Difficulty>,
+    seed: Option<u64>,
+    inputs: Vec<String>,
+    player: PlayerType,
 }
 
 fn main() {
     let parameters = parse();
-    println!("{:?}", parameters.problem);
 
-    let solver = Search::new(parameters.problem);
-    let solution = solver.run().unwrap();
-    println!("{solution:?}");
+    if parameters.command == Command::Generate {
+        if parameters.jigsaw.is_some() {
+            eprintln!("jigsaw puzzles cannot be generated");
+            std::process::exit(1);
+        }
+
+        if !parameters.constraints.is_empty() {
+            eprintln!("puzzles with constraints cannot be generated");
+            std::process::exit(1);
+        }
+
+        let seed = parameters.seed.unwrap_or_else(|| thread_rng().gen());
+        println!("seed: {seed}");
+        let puzzle = Generator::new(parameters.symmetry, parameters.difficulty, seed)
+            .with_variant(parameters.variant)
+            .generate()
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "no puzzle of that difficulty turned up from seed {seed}; try another seed"
+                );
+                std::process::exit(1);
+            });
+        println!("{}", show(&parameters, Renderer::new(), &puzzle));
+        println!("{}", Grade::from_problem(&puzzle));
+        return;
+    }
+
+    if parameters.command == Command::Batch {
+        let batch = Batch::new(parameters.solver).with_layout(layout(&parameters));
+        let stdout = std::io::stdout();
+        let mut writer = stdout.lock();
+        let throughput = if parameters.inputs.is_empty() {
+            batch.run(std::io::stdin().lock(), &mut writer)
+        } else {
+            // Separate the files by a newline, in case one is missing its trailing newline (blank lines are skipped).
+            let mut reader: Box<dyn Read> = Box::new(std::io::empty());
+
+            for input in parameters.inputs.iter() {
+                let file = File::open(input).unwrap_or_else(|error| {
+                    eprintln!("cannot open {input}: {error}");
+                    std::process::exit(1);
+                });
+                reader = Box::new(reader.chain(&b"\n"[..]).chain(file));
+            }
+
+            batch.run(BufReader::new(reader), &mut writer)
+        }
+        .unwrap_or_else(|error| {
+            eprintln!("batch failed: {error}");
+            std::process::exit(1);
+        });
+        eprintln!("{throughput}");
+        return;
+    }
+
+    // The problem may also name a file holding the puzzle.
+    let text = if Path::new(&parameters.problem).is_file() {
+        std::fs::read_to_string(&parameters.problem).unwrap_or_else(|error| {
+            eprintln!("cannot read {}: {error}", parameters.problem);
+            std::process::exit(1);
+        })
+    } else {
+        parameters.problem.clone()
+    };
+
+    if parameters.samurai {
+        if parameters.box_size != 3
+            || parameters.cages.is_some()
+            || parameters.relations.is_some()
+            || parameters.jigsaw.is_some()
+            || parameters.variant != Variant::Classic
+            || !parameters.constraints.is_empty()
+        {
+            eprintln!("samurai puzzles cannot be combined with other variants");
+            std::process::exit(1);
+        }
+
+        // The samurai grids have their own solver, reading & printing the 21 rows.
+        if parameters.solver != SolverType::DepthFirstSearch
+            || parameters.input_format.is_some()
+            || parameters.output_format.is_some()
+            || parameters.colour
+        {
+            eprintln!(
+                "--samurai cannot be combined with --solver, --input-format, --output-format or --colour"
+            );
+            std::process::exit(1);
+        }
+
+        return solve_samurai(&text);
+    }
+
+    if parameters.cages.is_some() && parameters.box_size != 3 {
+        eprintln!("cages are only supported on 9x9 grids");
+        std::process::exit(1);
+    }
+
+    // Killer puzzles are always solved by depth first search.
+    if parameters.cages.is_some() && parameters.solver != SolverType::DepthFirstSearch {
+        eprintln!("--cages cannot be combined with --solver");
+        std::process::exit(1);
+    }
+
+    if parameters.relations.is_some() && parameters.box_size != 3 {
+        eprintln!("relations are only supported on 9x9 grids");
+        std::process::exit(1);
+    }
+
+    // As with cages, the relations are always solved by depth first search.
+    if parameters.relations.is_some() && parameters.solver != SolverType::DepthFirstSearch {
+        eprintln!("--relations cannot be combined with --solver");
+        std::process::exit(1);
+    }
+
+    if parameters.cages.is_some() && parameters.relations.is_some() {
+        eprintln!("cages & relations cannot be combined");
+        std::process::exit(1);
+    }
+
+    // The other grid sizes are solved by depth first search, from & to the plain cells.
+    if parameters.box_size != 3
+        && (parameters.solver != SolverType::DepthFirstSearch
+            || parameters.input_format.is_some()
+            || parameters.output_format.is_some()
+            || parameters.colour
+            || parameters.jigsaw.is_some()
+            || parameters.variant != Variant::Classic
+            || !parameters.constraints.is_empty())
+    {
+        eprintln!(
+            "--box-size cannot be combined with --solver, --input-format, --output-format, --colour, --variant, --jigsaw or constraints"
+        );
+        std::process::exit(1);
+    }
+
+    let cages = parameters.cages.as_deref().map(read_cages);
+    let relations = parameters.relations.as_deref().map(read_relations);
+
+    match parameters.box_size {
+        2 => return solve_sized::<2>(&text),
+        4 => return solve_sized::<4>(&text),
+        5 => return solve_sized::<5>(&text),
+        _ => {}
+    }
+
+    let input_format = parameters
+        .input_format
+        .unwrap_or_else(|| Format::detect(&text));
+    let problem = input_format
+        .parse_with(&text, &layout(&parameters))
+        .unwrap_or_else(|error| {
+            eprintln!("invalid problem ({input_format}): {error}");
+            std::process::exit(1);
+        });
+
+    if parameters.command == Command::Play {
+        return play(&parameters, problem);
+    }
+
+    println!("{}", show(&parameters, Renderer::new(), &problem));
+
+    if parameters.command == Command::Grade {
+        println!("{}", Grade::from_problem(&problem));
+        return;
+    }
+
+    if parameters.command == Command::Explain {
+        let solve_path = Logical::new(problem.clone()).run();
+
+        for (i, step) in solve_path.steps().iter().enumerate() {
+            println!("{:>3}. {step}", i + 1);
+        }
+
+        let renderer = if solve_path.is_solved() {
+            Renderer::new().with_givens(&problem)
+        } else {
+            // Show the candidates left where the techniques got stuck.
+            Renderer::new()
+                .with_givens(&problem)
+                .with_pencil_marks(solve_path.pencil_marks())
+        };
+        println!("{}", show(&parameters, renderer, solve_path.result()));
+        println!("{}", Grade::from_solve_path(&solve_path));
+
+        if !solve_path.is_solved() {
+            println!("stuck: requires guessing");
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    if let Some(cages) = cages {
+        return solve_killer(&parameters, problem, cages);
+    }
+
+    if let Some(relations) = relations {
+        return solve_relations(&parameters, problem, relations);
+    }
+
+    let solver = parameters.solver.build(problem.clone());
+
+    match parameters.command {
+        Command::Solve => {
+            let solution = match solver.run() {
+                Ok(solution) => solution,
+                Err(error) => {
+                    eprintln!("{error}");
+                    std::process::exit(1);
+                }
+            };
+            println!(
+                "{}",
+                show(
+                    &parameters,
+                    Renderer::new().with_givens(&problem),
+                    &solution
+                )
+            );
+        }
+        Command::Check => {
+            // At least 2 solutions are required to tell unique from multiple.
+            let limit = parameters.limit.max(2);
+            let solutions = solver.solutions(limit).unwrap_or_else(|error| {
+                eprintln!("{error}");
+                std::process::exit(1);
+            });
+            let count = solutions.len();
+            let uniqueness = Uniqueness::from_solutions(solutions);
+
+            let plural = if count == 1 { "" } else { "s" };
+
+            if count == limit {
+                println!("{uniqueness} (at least {count} solution{plural})");
+            } else {
+                println!("{uniqueness} ({count} solution{plural})");
+            }
+
+            match uniqueness {
+                Uniqueness::Unique(solution) => println!(
+                    "{}",
+                    show(
+                        &parameters,
+                        Renderer::new().with_givens(&problem),
+                        &solution
+                    )
+                ),
+                _ => std::process::exit(1),
+            }
+        }
+        Command::Explain | Command::Grade | Command::Generate | Command::Batch | Command::Play => {
+            unreachable!("handled above")
+        }
+    }
 }
 
+// The options before the command which take a value, so whose next token is not the command.
+const VALUE_OPTIONS: [&str; 10] = [
+    "--solver",
+    "-s",
+    "--variant",
+    "-v",
+    "--jigsaw",
+    "-j",
+    "--input-format",
+    "-i",
+    "--output-format",
+    "-o",
+];
+
 fn parse() -> Parameters {
-    parse_tokens(|parser: GeneralParser| Ok(parser.parse()))
+    let tokens = with_default_command(std::env::args().skip(1).collect());
+    parse_tokens(|parser: GeneralParser| {
+        let tokens = tokens.iter().map(String::as_str).collect::<Vec<_>>();
+
+        if let Err(exit_code) = parser.parse_tokens(tokens.as_slice()) {
+            std::process::exit(exit_code);
+        }
+
+        Ok(())
+    })
+}
+
+// Before the commands, the puzzle was the only argument (`sudoku <sdm>`): so solve it when the command is left out.
+fn with_default_command(mut tokens: Vec<String>) -> Vec<String> {
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if VALUE_OPTIONS.contains(&tokens[i].as_str()) {
+            i += 2;
+        } else if tokens[i].starts_with('-') {
+            i += 1;
+        } else {
+            if Command::from_str(&tokens[i]).is_err() {
+                tokens.insert(i, Command::Solve.to_string());
+            }
+
+            break;
+        }
+    }
+
+    tokens
 }
 
 fn parse_tokens(parse_fn: impl FnOnce(GeneralParser) -> Result<(), i32>) -> Parameters {
-    let mut parameters = Parameters {
-        problem: SudokuProblem::default(),
-    };
+    let mut command = Command::Solve;
+    let mut solver = SolverType::DepthFirstSearch;
+    let mut variant = Variant::Classic;
+    let mut jigsaw: Option<String> = None;
+    let mut anti_knight = false;
+    let mut anti_king = false;
+    let mut non_consecutive = false;
+    let mut input_format: Option<Format> = None;
+    let mut output_format: Option<Format> = None;
+    let mut colour = false;
+    let mut limit: usize = 2;
+    let mut box_size: usize = 3;
+    let mut cages: Option<String> = None;
+    let mut relations: Option<String> = None;
+    let mut samurai = false;
+    let mut solve_problem = String::default();
+    let mut check_problem = String::default();
+    let mut explain_problem = String::default();
+    let mut grade_problem = String::default();
+    let mut symmetry = Symmetry::Rotational;
+    let mut difficulty: Option<Difficulty> = None;
+    let mut seed: Option<u64> = None;
+    let mut inputs: Vec<String> = Vec::default();
+    let mut player = PlayerType::Automatic;
+    let mut play_problem = String::default();
 
     let clp = CommandLineParser::new(env!("CARGO_BIN_NAME"));
-    let parser = clp.add(Parameter::argument(Scalar::new(&mut parameters.problem), "problem")
-        .help("The full sudoku puzzle in single line format (sdm).  This format interprets the cells from left to right, top to bottom.")
-        .meta(vec!["ex: 379000014060010070080009005435007000090040020000800436900700080040080050850000249"])).build();
+    let parser = clp
+        .add(Parameter::option(Scalar::new(&mut solver), "solver", Some('s'))
+            .help("The solver used to solve the puzzle.")
+            .choice(SolverType::DepthFirstSearch, "Depth first search, branching on the first unknown cell (default).")
+            .choice(SolverType::ConstraintPropagation, "Propagate naked & hidden singles before each branch.")
+            .choice(SolverType::DancingLinks, "Exact cover via Dancing Links (Algorithm X)."))
+        .add(Parameter::option(Scalar::new(&mut variant), "variant", Some('v'))
+            .help("The extra regions of the puzzle, each of which must hold the digits 1-9 exactly once.")
+            .choice(Variant::Classic, "No extra regions (default).")
+            .choice(Variant::X, "Sudoku-X: the two main diagonals.")
+            .choice(Variant::Hyper, "Hyper (Windoku): the four 3x3 windows between the squares."))
+        .add(Parameter::option(Optional::new(&mut jigsaw), "jigsaw", Some('j'))
+            .help("The irregular regions which replace the squares, or a file holding them.  A map of 81 characters from left to right, top to bottom, where the cells of each region share the same character.  There must be 9 regions of 9 connected cells.")
+            .meta(vec!["ex: aaabbbcccaabbbcccc..."]))
+        .add(Parameter::option(Switch::new(&mut anti_knight, true), "anti-knight", None)
+            .help("No two cells a knight's move apart may hold the same digit."))
+        .add(Parameter::option(Switch::new(&mut anti_king, true), "anti-king", None)
+            .help("No two cells a king's move apart (including diagonally) may hold the same digit."))
+        .add(Parameter::option(Switch::new(&mut non_consecutive, true), "non-consecutive", None)
+            .help("No two orthogonally adjacent cells may hold consecutive digits."))
+        .add(format_choices(Parameter::option(Optional::new(&mut input_format), "input-format", Some('i'))
+            .help("The layout of the problem.  Blanks may be any of '0', '.' or '_', and lines starting with '#' are comments.")
+            .meta(vec!["default: detected"])))
+        .add(format_choices(Parameter::option(Optional::new(&mut output_format), "output-format", Some('o'))
+            .help("The layout of the printed puzzles, instead of a grid with borders around the boxes.")
+            .meta(vec!["default: grid with borders"])))
+        .add(Parameter::option(Switch::new(&mut colour, true), "colour", Some('c'))
+            .help("Colour the grid with ANSI escapes: bold givens, green solved cells and dim candidates."))
+        .branch(Condition::new(Scalar::new(&mut command), "command")
+            .choice(Command::Solve, "Solve the puzzle.")
+            .choice(Command::Check, "Check whether the puzzle has no, a unique, or multiple solutions.  Exits with 1 unless the solution is unique.")
+            .choice(Command::Explain, "Solve the puzzle step by step using human techniques, printing each deduction.  Exits with 1 if the techniques get stuck.")
+            .choice(Command::Grade, "Grade the difficulty of the puzzle (easy, medium, hard, expert) by the hardest technique & number of steps needed to solve it.")
+            .choice(Command::Generate, "Generate a new puzzle with a unique solution.")
+            .choice(Command::Batch, "Solve a file of puzzles (one sdm per line) across all CPU cores.  Writes the solutions in input order, followed by throughput statistics (on stderr).")
+            .choice(Command::Play, "Play the puzzle in the terminal, one move at a time."))
+        .command(Command::Solve, |sub_command| {
+            sub_command
+                .add(Parameter::option(Scalar::new(&mut box_size), "box-size", Some('b'))
+                    .help("The size of the boxes, for grids other than 9x9.  These are solved by a depth first search on the cells with the fewest candidates, and printed without any formatting.")
+                    .choice(2, "4x4, with the values 1-4.")
+                    .choice(3, "9x9 (default).")
+                    .choice(4, "16x16, with the values 1-9 & A-G.")
+                    .choice(5, "25x25, with the values 1-9 & A-P."))
+                .add(Parameter::option(Optional::new(&mut cages), "cages", Some('k'))
+                    .help("A file of Killer cages, one per line as the sum and then its cells: '15: r1c1 r1c2 r2c1'.  The digits of each cage must add up to its sum without repeating.  Solved by a depth first search which prunes the cage sum combinations.")
+                    .meta(vec!["ex: cages.txt"]))
+                .add(Parameter::option(Optional::new(&mut relations), "relations", Some('r'))
+                    .help("A file of thermometers, arrows & Kropki dots, one per line as the kind and then its cells: 'thermo: r1c1 r1c2 r2c3'.  Thermometers increase from the bulb, arrows sum to the digit in their circle (the first cell), white dots join consecutive digits and black dots digits in a 1:2 ratio.  Solved by a depth first search which prunes the candidates along each relation.")
+                    .meta(vec!["ex: relations.txt"]))
+                .add(Parameter::option(Switch::new(&mut samurai, true), "samurai", Some('m'))
+                    .help("Solve a Samurai puzzle: five 9x9 grids in 21 rows of 21 cells, where the corner grids each share a box with the centre grid.  The gaps between the corner grids are spaces.  Solved by a depth first search which propagates the singles through the shared boxes."))
+                .add(problem_argument(&mut solve_problem))
+        })
+        .command(Command::Check, |sub_command| {
+            sub_command
+                .add(Parameter::option(Scalar::new(&mut limit), "limit", Some('l'))
+                    .help("The maximum number of solutions to count (at least 2).")
+                    .meta(vec!["default: 2"]))
+                .add(problem_argument(&mut check_problem))
+        })
+        .command(Command::Explain, |sub_command| {
+            sub_command.add(problem_argument(&mut explain_problem))
+        })
+        .command(Command::Grade, |sub_command| {
+            sub_command.add(problem_argument(&mut grade_problem))
+        })
+        .command(Command::Generate, |sub_command| {
+            sub_command
+                .add(Parameter::option(Scalar::new(&mut symmetry), "symmetry", Some('y'))
+                    .help("The symmetry of the clues.")
+                    .choice(Symmetry::None, "No symmetry.")
+                    .choice(Symmetry::Rotational, "180 degree rotational symmetry (default).")
+                    .choice(Symmetry::Mirror, "Left-right mirror symmetry."))
+                .add(Parameter::option(Optional::new(&mut difficulty), "difficulty", Some('d'))
+                    .help("The target difficulty.  Puzzles are regenerated (up to 1000 times) until one grades to exactly this difficulty.")
+                    .choice(Difficulty::Easy, "Only singles are needed.")
+                    .choice(Difficulty::Medium, "Pairs & intersections are needed.")
+                    .choice(Difficulty::Hard, "Triples, fish, wings or coloring are needed.")
+                    .choice(Difficulty::Expert, "Guessing is required."))
+                .add(Parameter::option(Optional::new(&mut seed), "seed", None)
+                    .help("The random seed, for reproducible puzzles.")
+                    .meta(vec!["default: random"]))
+        })
+        .command(Command::Batch, |sub_command| {
+            sub_command.add(Parameter::argument(Collection::new(&mut inputs, Nargs::Any), "input")
+                .help("The files of puzzles, read in order.  Reads from stdin when no files are given.")
+                .meta(vec!["ex: puzzles.txt"]))
+        })
+        .command(Command::Play, |sub_command| {
+            sub_command
+                .add(Parameter::argument(Scalar::new(&mut player), "player")
+                    .help("The type[-name] of the player (ex: Manual-Alice, Automatic, etc).  Manual players type in their moves (r3c5=7 to place a digit, r3c5+1,2 for pencil marks, undo, redo & quit), and assisted ones may also ask for a hint or check for wrong digits, while automatic players fill in the solution."))
+                .add(problem_argument(&mut play_problem))
+        })
+        .build();
     // The parse_fn signature is a `Result`.
     // However, since `GeneralParser::parse` does not return an error (it uses `std::process::exit` under the hood), the `Err` case is only reached via test.
     parse_fn(parser).expect("test-reachable-only");
-    parameters
+
+    let problem = match command {
+        Command::Solve => solve_problem,
+        Command::Check => check_problem,
+        Command::Explain => explain_problem,
+        Command::Grade => grade_problem,
+        Command::Play => play_problem,
+        Command::Generate | Command::Batch => String::default(),
+    };
+    let constraints = [
+        (anti_knight, Constraint::AntiKnight),
+        (anti_king, Constraint::AntiKing),
+        (non_consecutive, Constraint::NonConsecutive),
+    ]
+    .iter()
+    .filter_map(|(enabled, constraint)| enabled.then_some(*constraint))
+    .collect();
+
+    Parameters {
+        command,
+        solver,
+        variant,
+        jigsaw,
+        constraints,
+        input_format,
+        output_format,
+        colour,
+        problem,
+        limit,
+        box_size,
+        cages,
+        relations,
+        samurai,
+        symmetry,
+        difficulty,
+        seed,
+        inputs,
+        player,
+    }
+}
+
+// An empty problem with the jigsaw, extra regions & constraints, onto which the puzzles are parsed.
+fn layout(parameters: &Parameters) -> SudokuProblem {
+    let mut layout = SudokuProblem::default();
+
+    if let Some(map) = &parameters.jigsaw {
+        // The map may also name a file holding it.
+        let map = if Path::new(map).is_file() {
+            std::fs::read_to_string(map).unwrap_or_else(|error| {
+                eprintln!("cannot read {map}: {error}");
+                std::process::exit(1);
+            })
+        } else {
+            map.clone()
+        };
+        let jigsaw = Jigsaw::from_str(&map).unwrap_or_else(|error| {
+            eprintln!("invalid jigsaw: {error}");
+            std::process::exit(1);
+        });
+        layout = layout
+            .with_jigsaw(&jigsaw)
+            .expect("the empty grid has no duplicates");
+    }
+
+    layout = layout
+        .with_variant(parameters.variant)
+        .expect("the empty grid has no duplicates");
+
+    for constraint in &parameters.constraints {
+        layout = layout
+            .with_constraint(*constraint)
+            .expect("the empty grid has no duplicates");
+    }
+
+    layout
+}
+
+// Blanks may be any of '0', '.' or '_', and whitespace is ignored.
+fn solve_sized<const N: usize>(text: &str) {
+    let problem = SizedProblem::<N>::from_str(text).unwrap_or_else(|error| {
+        eprintln!("invalid problem: {error}");
+        std::process::exit(1);
+    });
+    println!("{problem}\n");
+    let solution = Search::new(problem)
+        .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+        .run()
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(1);
+        });
+    println!("{solution}");
+}
+
+fn read_cages(path: &str) -> Cages {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("cannot read {path}: {error}");
+        std::process::exit(1);
+    });
+    Cages::from_str(&text).unwrap_or_else(|error| {
+        eprintln!("invalid cages ({path}): {error}");
+        std::process::exit(1);
+    })
+}
+
+fn solve_killer(parameters: &Parameters, problem: SudokuProblem, cages: Cages) {
+    let solution = Search::new(KillerProblem::new(problem.clone(), cages))
+        .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+        .run()
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(1);
+        });
+    println!(
+        "{}",
+        show(
+            parameters,
+            Renderer::new().with_givens(&problem),
+            solution.problem()
+        )
+    );
+}
+
+fn play(parameters: &Parameters, problem: SudokuProblem) {
+    let mut game = Game::new(problem.clone()).with_colour(parameters.colour);
+    let stdout = std::io::stdout();
+    let mut writer = stdout.lock();
+    let solved = match &parameters.player {
+        PlayerType::Manual(name) => {
+            println!("{name} to play");
+            game.run(std::io::stdin().lock(), &mut writer)
+        }
+        PlayerType::ManualWithAssistance(name) => {
+            // The hints & checks are only sound against a unique solution.
+            let solution = match parameters.solver.build(problem).uniqueness() {
+                Ok(Uniqueness::Unique(solution)) => *solution,
+                Ok(uniqueness) => {
+                    eprintln!(
+                        "assistance needs a unique solution, but the puzzle has {uniqueness}"
+                    );
+                    std::process::exit(1);
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    std::process::exit(1);
+                }
+            };
+            println!("{name} to play, with assistance");
+            game = game.with_solution(solution);
+            game.run(std::io::stdin().lock(), &mut writer)
+        }
+        PlayerType::Automatic | PlayerType::AutomaticWithAssistance(_) => {
+            let solution = parameters
+                .solver
+                .build(problem)
+                .run()
+                .unwrap_or_else(|error| {
+                    eprintln!("{error}");
+                    std::process::exit(1);
+                });
+            game.autoplay(&solution, &mut writer)
+        }
+    }
+    .unwrap_or_else(|error| {
+        eprintln!("play failed: {error}");
+        std::process::exit(1);
+    });
+
+    if !solved {
+        std::process::exit(1);
+    }
+}
+
+fn solve_samurai(text: &str) {
+    let problem = SamuraiProblem::from_str(text).unwrap_or_else(|error| {
+        eprintln!("invalid samurai problem: {error}");
+        std::process::exit(1);
+    });
+    println!("{problem}\n");
+    let solution = Samurai::new(problem).run().unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    });
+    println!("{solution}");
+}
+
+fn read_relations(path: &str) -> Relations {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("cannot read {path}: {error}");
+        std::process::exit(1);
+    });
+    Relations::from_str(&text).unwrap_or_else(|error| {
+        eprintln!("invalid relations ({path}): {error}");
+        std::process::exit(1);
+    })
+}
+
+fn solve_relations(parameters: &Parameters, problem: SudokuProblem, relations: Relations) {
+    let solution = Search::new(RelationProblem::new(problem.clone(), relations))
+        .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+        .run()
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(1);
+        });
+    println!(
+        "{}",
+        show(
+            parameters,
+            Renderer::new().with_givens(&problem),
+            solution.problem()
+        )
+    );
+}
+
+// The puzzle in the requested output format, or else rendered as a grid.
+fn show(parameters: &Parameters, renderer: Renderer<'_>, problem: &SudokuProblem) -> String {
+    match parameters.output_format {
+        Some(format) => format.write(problem),
+        None => renderer.with_colour(parameters.colour).render(problem),
+    }
+}
+
+fn problem_argument(problem: &mut String) -> Parameter<'_, String> {
+    Parameter::argument(Scalar::new(problem), "problem")
+        .help("The full sudoku puzzle, or a file holding it.  Each format interprets the cells from left to right, top to bottom.")
+        .meta(vec!["ex: 379000014060010070080009005435007000090040020000800436900700080040080050850000249"])
+}
+
+fn format_choices(parameter: Parameter<'_, Format>) -> Parameter<'_, Format> {
+    parameter
+        .choice(Format::Sdm, "A single line of 81 digits, with '0' for blanks.")
+        .choice(Format::Line, "A single line of 81 characters, with '.' for blanks.")
+        .choice(Format::Grid, "9 lines of 9 digits, with '0' for blanks.")
+        .choice(Format::Sdk, "9 lines of 9 characters, with '.' for blanks (SadMan Sudoku).")
+        .choice(Format::Ss, "9 lines of 9 characters, with '.' for blanks and '|' & '-' between the boxes (Simple Sudoku).")
 }
 
 #[cfg(test)]
@@ -48,4 +777,309 @@ mod tests {
         // Execute & verify
         parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
     }
+
+    #[test]
+    fn parse_solver() {
+        // Setup
+        let sdm =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let tokens = vec!["--solver", "dlx", "solve", sdm];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.solver, SolverType::DancingLinks);
+        assert_eq!(parameters.problem, sdm);
+    }
+
+    #[test]
+    fn parse_default_command() {
+        // Setup
+        let sdm =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let tokens = ["--solver", "dlx", "-c", sdm]
+            .iter()
+            .map(|token| token.to_string())
+            .collect();
+
+        // Execute
+        let tokens = with_default_command(tokens);
+        let tokens = tokens.iter().map(String::as_str).collect::<Vec<_>>();
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(tokens, ["--solver", "dlx", "-c", "solve", sdm]);
+        assert_eq!(parameters.command, Command::Solve);
+        assert_eq!(parameters.solver, SolverType::DancingLinks);
+        assert_eq!(parameters.problem, sdm);
+
+        // An explicit command is left alone.
+        let tokens = vec!["-v".to_string(), "x".to_string(), "check".to_string()];
+        assert_eq!(with_default_command(tokens.clone()), tokens);
+    }
+
+    #[test]
+    fn parse_default_solver() {
+        // Setup
+        let sdm =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let tokens = vec!["solve", sdm];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Solve);
+        assert_eq!(parameters.solver, SolverType::DepthFirstSearch);
+        assert_eq!(parameters.box_size, 3);
+    }
+
+    #[test]
+    fn parse_check() {
+        // Setup
+        let sdm =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let tokens = vec!["check", "--limit", "10", sdm];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Check);
+        assert_eq!(parameters.limit, 10);
+        assert_eq!(parameters.problem, sdm);
+    }
+
+    #[test]
+    fn parse_explain() {
+        // Setup
+        let sdm =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let tokens = vec!["explain", sdm];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Explain);
+        assert_eq!(parameters.problem, sdm);
+    }
+
+    #[test]
+    fn parse_grade() {
+        // Setup
+        let sdm =
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+        let tokens = vec!["grade", sdm];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Grade);
+        assert_eq!(parameters.problem, sdm);
+    }
+
+    #[test]
+    fn parse_generate() {
+        // Setup
+        let tokens = vec![
+            "generate",
+            "--symmetry",
+            "mirror",
+            "--difficulty",
+            "hard",
+            "--seed",
+            "42",
+        ];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Generate);
+        assert_eq!(parameters.symmetry, Symmetry::Mirror);
+        assert_eq!(parameters.difficulty, Some(Difficulty::Hard));
+        assert_eq!(parameters.seed, Some(42));
+    }
+
+    #[test]
+    fn parse_generate_defaults() {
+        // Setup
+        let tokens = vec!["generate"];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.symmetry, Symmetry::Rotational);
+        assert_eq!(parameters.difficulty, None);
+        assert_eq!(parameters.seed, None);
+    }
+
+    #[test]
+    fn parse_batch() {
+        // Setup
+        let tokens = vec!["--solver", "propagation", "batch", "a.txt", "b.txt"];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Batch);
+        assert_eq!(parameters.solver, SolverType::ConstraintPropagation);
+        assert_eq!(parameters.inputs, vec!["a.txt", "b.txt"]);
+
+        // Reads from stdin.
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["batch"]));
+        assert_eq!(parameters.command, Command::Batch);
+        assert!(parameters.inputs.is_empty());
+    }
+
+    #[test]
+    fn parse_formats() {
+        // Setup
+        let tokens = vec![
+            "--input-format",
+            "line",
+            "--output-format",
+            "ss",
+            "solve",
+            "379....14.6..1..7..8...9..5435..7....9..4..2....8..4369..7...8..4..8..5.85....249",
+        ];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.input_format, Some(Format::Line));
+        assert_eq!(parameters.output_format, Some(Format::Ss));
+        assert!(!parameters.colour);
+    }
+
+    #[test]
+    fn parse_default_formats() {
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["generate"]));
+        assert_eq!(parameters.input_format, None);
+        assert_eq!(parameters.output_format, None);
+        assert!(!parameters.colour);
+    }
+
+    #[test]
+    fn parse_colour() {
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["--colour", "generate"]));
+        assert!(parameters.colour);
+    }
+
+    #[test]
+    fn parse_box_size() {
+        // Setup
+        let tokens = vec!["solve", "--box-size", "2", "1... ..2. .3.. ...4"];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.box_size, 2);
+        assert_eq!(parameters.problem, "1... ..2. .3.. ...4");
+    }
+
+    #[test]
+    fn parse_variant() {
+        let parameters =
+            parse_tokens(|parser| parser.parse_tokens(&["--variant", "x", "generate"]));
+        assert_eq!(parameters.variant, Variant::X);
+
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["generate"]));
+        assert_eq!(parameters.variant, Variant::Classic);
+    }
+
+    #[test]
+    fn parse_cages() {
+        // Setup
+        let problem = ".".repeat(81);
+        let tokens = vec!["solve", "--cages", "cages.txt", problem.as_str()];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.cages, Some("cages.txt".to_string()));
+        assert_eq!(parameters.problem, problem);
+    }
+
+    #[test]
+    fn parse_relations() {
+        let parameters = parse_tokens(|parser| {
+            parser.parse_tokens(&["solve", "-r", "relations.txt", "puzzle.txt"])
+        });
+        assert_eq!(parameters.relations, Some("relations.txt".to_string()));
+        assert_eq!(parameters.cages, None);
+        assert_eq!(parameters.problem, "puzzle.txt");
+    }
+
+    #[test]
+    fn parse_samurai() {
+        let parameters =
+            parse_tokens(|parser| parser.parse_tokens(&["solve", "--samurai", "samurai.txt"]));
+        assert!(parameters.samurai);
+        assert_eq!(parameters.problem, "samurai.txt");
+
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["solve", "samurai.txt"]));
+        assert!(!parameters.samurai);
+    }
+
+    #[test]
+    fn parse_jigsaw() {
+        let parameters = parse_tokens(|parser| {
+            parser.parse_tokens(&["--jigsaw", "jigsaw.txt", "grade", "puzzle.txt"])
+        });
+        assert_eq!(parameters.jigsaw, Some("jigsaw.txt".to_string()));
+        assert_eq!(parameters.problem, "puzzle.txt");
+
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["generate"]));
+        assert_eq!(parameters.jigsaw, None);
+    }
+
+    #[test]
+    fn parse_constraints() {
+        let parameters = parse_tokens(|parser| {
+            parser.parse_tokens(&["--non-consecutive", "--anti-knight", "check", "puzzle.txt"])
+        });
+        assert_eq!(
+            parameters.constraints,
+            [Constraint::AntiKnight, Constraint::NonConsecutive]
+        );
+
+        let parameters = parse_tokens(|parser| parser.parse_tokens(&["check", "puzzle.txt"]));
+        assert!(parameters.constraints.is_empty());
+    }
+
+    #[test]
+    fn parse_play() {
+        // Setup
+        let problem = ".".repeat(81);
+        let tokens = vec!["play", "Manual-alice", problem.as_str()];
+
+        // Execute
+        let parameters = parse_tokens(|parser| parser.parse_tokens(tokens.as_slice()));
+
+        // Verify
+        assert_eq!(parameters.command, Command::Play);
+        assert_eq!(parameters.player, PlayerType::Manual("alice".to_string()));
+        assert_eq!(parameters.problem, problem);
+
+        let parameters =
+            parse_tokens(|parser| parser.parse_tokens(&["play", "Automatic", "puzzle.txt"]));
+        assert_eq!(parameters.player, PlayerType::Automatic);
+
+        let parameters = parse_tokens(|parser| {
+            parser.parse_tokens(&["play", "ManualWithAssistance-bob", "puzzle.txt"])
+        });
+        assert_eq!(
+            parameters.player,
+            PlayerType::ManualWithAssistance("bob".to_string())
+        );
+    }
 }
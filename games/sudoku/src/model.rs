@@ -1,2 +1,15 @@
 pub mod board;
 pub mod cell;
+pub mod constraint;
+pub mod format;
+pub mod jigsaw;
+pub mod killer;
+pub mod pencil;
+pub mod puzzle;
+pub mod relation;
+pub mod render;
+pub mod samurai;
+pub mod sized;
+pub mod transform;
+pub(crate) mod unit;
+pub mod variant;
@@ -1,40 +1,196 @@
 use crate::model::cell::{Cell, SudokuValue};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::model::constraint::Constraint;
+use crate::model::jigsaw::Jigsaw;
+use crate::model::render::Renderer;
+use crate::model::sized::symbol;
+use crate::model::unit::{Units, CLASSIC, UNITS};
+use crate::model::variant::Variant;
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Formatter;
 use std::str::FromStr;
+use std::sync::Arc;
 
-lazy_static! {
-    static ref PROBLEM_REGEX: Regex = Regex::new(r"^[0-9]{81}$").unwrap();
+const ALL_CANDIDATES: u16 = 0b1_1111_1111;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    WrongLength {
+        expected: usize,
+        length: usize,
+    },
+    WrongRowCount {
+        rows: usize,
+    },
+    // The `row` counts from 1.
+    WrongRowLength {
+        row: usize,
+        length: usize,
+    },
+    // The `position` counts characters from 1.
+    InvalidCharacter {
+        position: usize,
+        character: char,
+    },
+    // An invalid character in a grid of `side` x `side` cells (see `model::sized`).
+    InvalidSymbol {
+        position: usize,
+        character: char,
+        side: usize,
+    },
+    // The `first` & `second` cells share the `unit` (the first of them, when there are several).
+    DuplicateGiven {
+        value: SudokuValue,
+        unit: Unit,
+        first: Cell,
+        second: Cell,
+    },
+    // As `DuplicateGiven`, in a grid of `side` x `side` cells: the cells are (row, column), counting from 1.
+    DuplicateSymbol {
+        symbol: char,
+        unit: Unit,
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+    // The `first` & `second` cells are related by a constraint beyond the units.
+    BrokenConstraint {
+        constraint: Constraint,
+        first: Cell,
+        second: Cell,
+    },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::WrongLength { expected, length } => {
+                write!(f, "expected {} cells, but found {}", expected, length)
+            }
+            ParseError::WrongRowCount { rows } => {
+                write!(f, "expected 9 rows, but found {}", rows)
+            }
+            ParseError::WrongRowLength { row, length } => {
+                write!(f, "expected 9 cells in row {}, but found {}", row, length)
+            }
+            ParseError::InvalidCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "invalid character '{}' at position {} (expected 0-9)",
+                character, position
+            ),
+            ParseError::InvalidSymbol {
+                position,
+                character,
+                side,
+            } => write!(
+                f,
+                "invalid character '{}' at position {} (expected 0-{})",
+                character,
+                position,
+                symbol(*side)
+            ),
+            ParseError::DuplicateGiven {
+                value,
+                unit,
+                first,
+                second,
+            } => write!(
+                f,
+                "duplicate {:?} given in {} (at {} and {})",
+                value, unit, first, second
+            ),
+            ParseError::DuplicateSymbol {
+                symbol,
+                unit,
+                first,
+                second,
+            } => write!(
+                f,
+                "duplicate {} given in {} (at r{}c{} and r{}c{})",
+                symbol, unit, first.0, first.1, second.0, second.1
+            ),
+            ParseError::BrokenConstraint {
+                constraint,
+                first,
+                second,
+            } => write!(
+                f,
+                "the givens at {} and {} break the {} constraint",
+                first, second, constraint
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// A unit of the grid, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Square(usize),
+    // A region of a jigsaw, in place of the squares.
+    Jigsaw(usize),
+    // An extra region of a variant, such as a diagonal or a window.
+    Region(usize),
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Row(row) => write!(f, "row {}", row),
+            Unit::Column(column) => write!(f, "column {}", column),
+            Unit::Square(square) => write!(f, "square {}", square),
+            Unit::Jigsaw(region) => write!(f, "jigsaw region {}", region),
+            Unit::Region(region) => write!(f, "extra region {}", region),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct SudokuProblem {
     puzzle: [SudokuValue; 9 * 9],
+    // The candidate digits of each cell, as a bitmask (bit `d` for the count index `d`).
+    // Unknown cells hold the digits not yet used by any of their units; known cells hold only their own digit.
+    candidates: [u16; 9 * 9],
+    // The digits used in each of the `units`, as a bitmask.
+    used: Vec<u16>,
+    unknowns: usize,
+    valid: bool,
+    // The rows, columns & squares (or the regions of a jigsaw), plus any extra regions of the variant.
+    units: Arc<Units>,
 }
 
 impl Default for SudokuProblem {
     fn default() -> Self {
         Self {
             puzzle: [SudokuValue::Unknown; 9 * 9],
+            candidates: [ALL_CANDIDATES; 9 * 9],
+            used: vec![0; CLASSIC.units.len()],
+            unknowns: 9 * 9,
+            valid: true,
+            units: CLASSIC.clone(),
         }
     }
 }
 
 impl FromStr for SudokuProblem {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(sdm: &str) -> Result<Self, Self::Err> {
-        let puzzle: Result<[SudokuValue; 9 * 9], _> = sdm
+        let values = sdm
             .chars()
-            .map(SudokuValue::try_from)
-            .collect::<Result<Vec<SudokuValue>, ()>>()?
-            .try_into();
-        match puzzle {
-            Ok(puzzle) => Ok(SudokuProblem::new(puzzle)),
-            Err(_) => Err(()),
-        }
+            .enumerate()
+            .map(|(i, character)| {
+                SudokuValue::try_from(character).map_err(|_| ParseError::InvalidCharacter {
+                    position: i + 1,
+                    character,
+                })
+            })
+            .collect::<Result<Vec<SudokuValue>, ParseError>>()?;
+        SudokuProblem::from_values(values)
     }
 }
 
@@ -48,116 +204,316 @@ impl std::fmt::Debug for SudokuProblem {
     }
 }
 
-impl SudokuProblem {
-    fn new(puzzle: [SudokuValue; 9 * 9]) -> Self {
-        Self { puzzle }
+impl std::fmt::Display for SudokuProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Renderer::new().render(self))
     }
+}
 
-    fn rows(&self) -> Vec<[SudokuValue; 9]> {
-        let mut rows: Vec<[SudokuValue; 9]> = Vec::with_capacity(9);
+impl SudokuProblem {
+    fn build(puzzle: [SudokuValue; 9 * 9], units: Arc<Units>) -> Self {
+        let mut problem = SudokuProblem {
+            used: vec![0; units.units.len()],
+            units,
+            ..SudokuProblem::default()
+        };
 
-        for i in 0..9 {
-            let row = &self.puzzle[i * 9..(i * 9) + 9];
-            rows.push(
-                row.try_into()
-                    .expect("slice must fit into [SudokuValue; 9]"),
-            );
+        for (index, value) in puzzle.iter().enumerate() {
+            problem.place(index, *value);
         }
 
-        rows
+        problem
     }
 
-    fn columns(&self) -> Vec<[SudokuValue; 9]> {
-        let mut columns: Vec<[SudokuValue; 9]> = Vec::with_capacity(9);
+    // Build a problem from the values of its cells, from left to right, top to bottom.
+    pub(crate) fn from_values(values: Vec<SudokuValue>) -> Result<Self, ParseError> {
+        SudokuProblem::default().with_values(values)
+    }
 
-        for j in 0..9 {
-            let mut column: [SudokuValue; 9] = [SudokuValue::Unknown; 9];
+    // Build a problem from the values of its cells, with the same units as this one (whose own values are ignored).
+    pub(crate) fn with_values(&self, values: Vec<SudokuValue>) -> Result<Self, ParseError> {
+        let length = values.len();
+        let puzzle: [SudokuValue; 9 * 9] =
+            values.try_into().map_err(|_| ParseError::WrongLength {
+                expected: 9 * 9,
+                length,
+            })?;
+        SudokuProblem::build(puzzle, self.units.clone()).checked()
+    }
 
-            for i in 0..9 {
-                let index = (&i * 9) + &j;
-                column[i] = self.puzzle[index];
-            }
+    // Add extra regions, each of 9 distinct cells which (like the rows, columns & squares) must hold every digit once.
+    // Fails when the givens repeat a digit within a region.
+    pub fn with_regions(&self, regions: &[[Cell; 9]]) -> Result<Self, ParseError> {
+        let regions = self
+            .units
+            .regions()
+            .iter()
+            .copied()
+            .chain(regions.iter().map(|region| region.map(|cell| cell.index)))
+            .collect();
+        self.with_units(self.units.boxes(), regions, self.units.constraints.clone())
+    }
+
+    // Replace the squares by the irregular regions of the jigsaw, keeping any extra regions.
+    // Fails when the givens repeat a digit within a region.
+    pub fn with_jigsaw(&self, jigsaw: &Jigsaw) -> Result<Self, ParseError> {
+        let boxes = jigsaw
+            .regions()
+            .iter()
+            .map(|region| region.map(|cell| cell.index))
+            .collect::<Vec<_>>();
+        self.with_units(
+            &boxes,
+            self.units.regions().to_vec(),
+            self.units.constraints.clone(),
+        )
+    }
 
-            columns.push(column);
+    // Add a constraint beyond the units, which may be combined with any other.
+    // Fails when the givens break the constraint.
+    pub fn with_constraint(&self, constraint: Constraint) -> Result<Self, ParseError> {
+        let mut constraints = self.units.constraints.clone();
+
+        if !constraints.contains(&constraint) {
+            constraints.push(constraint);
         }
 
-        columns
+        self.with_units(
+            self.units.boxes(),
+            self.units.regions().to_vec(),
+            constraints,
+        )
     }
 
-    fn squares(&self) -> Vec<[SudokuValue; 9]> {
-        let mut squares: Vec<[SudokuValue; 9]> = Vec::with_capacity(9);
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.units.constraints
+    }
+
+    fn with_units(
+        &self,
+        boxes: &[[usize; 9]],
+        regions: Vec<[usize; 9]>,
+        constraints: Vec<Constraint>,
+    ) -> Result<Self, ParseError> {
+        let units = Units::new(boxes, regions).with_constraints(constraints);
+        SudokuProblem::build(self.puzzle, Arc::new(units)).checked()
+    }
+
+    pub fn with_variant(&self, variant: Variant) -> Result<Self, ParseError> {
+        self.with_regions(&variant.regions())
+    }
+
+    // The extra regions (beyond the rows, columns & squares) of the problem.
+    pub fn regions(&self) -> Vec<[Cell; 9]> {
+        self.units
+            .regions()
+            .iter()
+            .map(|region| region.map(|index| Cell::index(index).expect("must be a valid index")))
+            .collect()
+    }
+
+    pub(crate) fn units(&self) -> &Units {
+        &self.units
+    }
+
+    fn checked(self) -> Result<Self, ParseError> {
+        match self.duplicate() {
+            Some(error) => Err(error),
+            None => Ok(self),
+        }
+    }
+
+    // The first given which repeats an earlier given in one of its units, or else breaks a constraint with it.
+    fn duplicate(&self) -> Option<ParseError> {
+        if self.valid {
+            return None;
+        }
+
+        for (index, value) in self.puzzle.iter().enumerate() {
+            let digit = match value.to_count_index() {
+                Some(digit) => digit,
+                None => continue,
+            };
+            let second = Cell::index(index).expect("must be a valid index");
 
-        for x in 0..9 {
-            let mut square: [SudokuValue; 9] = [SudokuValue::Unknown; 9];
+            if let Some(peer) = self.units.peers[index]
+                .iter()
+                .find(|peer| **peer < index && self.puzzle[**peer] == *value)
+            {
+                let first = Cell::index(*peer).expect("must be a valid index");
 
-            let a = &x / 3;
-            let b = &x % 3;
-            let initial = (a * 9 * 3) + (b * 3);
+                if let Some(unit) = self.units.shared_unit(*peer, index) {
+                    return Some(ParseError::DuplicateGiven {
+                        value: *value,
+                        unit,
+                        first,
+                        second,
+                    });
+                }
 
-            for y in 0..9 {
-                square[y] = self.puzzle[&initial + ((&y / 3) * 9) + (&y % 3)];
+                let constraint = self
+                    .units
+                    .constraints
+                    .iter()
+                    .find(|constraint| {
+                        constraint.is_distinct() && constraint.cells(&first).contains(&second)
+                    })
+                    .expect("the peers beyond the units must be related by a constraint");
+                return Some(ParseError::BrokenConstraint {
+                    constraint: *constraint,
+                    first,
+                    second,
+                });
             }
 
-            squares.push(square);
+            if let Some(neighbour) = self.units.neighbours[index].iter().find(|neighbour| {
+                **neighbour < index && self.candidates[**neighbour] & consecutive(1 << digit) != 0
+            }) {
+                return Some(ParseError::BrokenConstraint {
+                    constraint: Constraint::NonConsecutive,
+                    first: Cell::index(*neighbour).expect("must be a valid index"),
+                    second,
+                });
+            }
         }
 
-        squares
+        None
     }
 
-    pub fn is_valid(&self) -> bool {
-        let valid_check = |section: &[SudokuValue]| {
-            let mut counts: [usize; 9] = [0; 9];
+    // The known cells which break a rule with another known cell: repeating its digit, or else (under the non-consecutive
+    // constraint) holding a consecutive digit beside it.  Only an invalid problem has any.
+    pub fn conflicts(&self) -> Vec<Cell> {
+        if self.valid {
+            return Vec::default();
+        }
+
+        (0..9 * 9)
+            .filter(|index| {
+                let digit = match self.puzzle[*index].to_count_index() {
+                    Some(digit) => digit,
+                    None => return false,
+                };
+                let known = |other: &&usize| self.puzzle[**other] != SudokuValue::Unknown;
+
+                self.units.peers[*index]
+                    .iter()
+                    .any(|peer| self.puzzle[*peer] == self.puzzle[*index])
+                    || self.units.neighbours[*index]
+                        .iter()
+                        .filter(known)
+                        .any(|neighbour| self.candidates[*neighbour] & consecutive(1 << digit) != 0)
+            })
+            .map(|index| Cell::index(index).expect("must be a valid index"))
+            .collect()
+    }
 
-            for value in section {
-                if let Some(index) = value.to_count_index() {
-                    counts[*&index] += 1;
+    // Place a value into an unknown cell, updating the candidate & used masks incrementally.
+    fn place(&mut self, index: usize, value: SudokuValue) {
+        debug_assert!(self.puzzle[index] == SudokuValue::Unknown);
 
-                    if counts[*&index] > 1 {
-                        return false;
-                    }
+        if let Some(digit) = value.to_count_index() {
+            let bit = 1 << digit;
+            self.puzzle[index] = value;
+            self.candidates[index] = bit;
+            self.unknowns -= 1;
+
+            for unit in self.units.cell_units[index].iter() {
+                if self.used[*unit] & bit != 0 {
+                    self.valid = false;
                 }
+
+                self.used[*unit] |= bit;
             }
 
-            true
-        };
+            // The peers by an anti-knight (or anti-king) move share no unit, so have no mask to check.
+            let distinct = self.units.constraints.iter().any(|c| c.is_distinct());
 
-        self.rows().iter().all(|section| valid_check(&section[..]))
-            && self
-                .columns()
-                .iter()
-                .all(|section| valid_check(&section[..]))
-            && self
-                .squares()
-                .iter()
-                .all(|section| valid_check(&section[..]))
-    }
+            for peer in self.units.peers[index].iter() {
+                if self.puzzle[*peer] == SudokuValue::Unknown {
+                    self.candidates[*peer] &= !bit;
+                } else if distinct && self.puzzle[*peer] == value {
+                    self.valid = false;
+                }
+            }
 
-    pub fn is_complete(&self) -> bool {
-        let solved_check = |section: &[SudokuValue]| {
-            for value in section {
-                if let None = value.to_count_index() {
-                    return false;
+            for neighbour in self.units.neighbours[index].iter() {
+                if self.puzzle[*neighbour] == SudokuValue::Unknown {
+                    self.candidates[*neighbour] &= !consecutive(bit);
+                } else if self.candidates[*neighbour] & consecutive(bit) != 0 {
+                    self.valid = false;
                 }
             }
+        }
+    }
 
-            true
-        };
+    fn sections(&self, units: &[[usize; 9]]) -> Vec<[SudokuValue; 9]> {
+        units
+            .iter()
+            .map(|unit| {
+                let mut section: [SudokuValue; 9] = [SudokuValue::Unknown; 9];
 
-        self.rows().iter().all(|section| solved_check(&section[..]))
-            && self
-                .columns()
-                .iter()
-                .all(|section| solved_check(&section[..]))
-            && self
-                .squares()
-                .iter()
-                .all(|section| solved_check(&section[..]))
+                for (value, index) in section.iter_mut().zip(unit.iter()) {
+                    *value = self.puzzle[*index];
+                }
+
+                section
+            })
+            .collect()
+    }
+
+    pub fn rows(&self) -> Vec<[SudokuValue; 9]> {
+        self.sections(&UNITS[0..9])
+    }
+
+    pub fn columns(&self) -> Vec<[SudokuValue; 9]> {
+        self.sections(&UNITS[9..18])
+    }
+
+    // The squares, or else the irregular regions of a jigsaw.
+    pub fn squares(&self) -> Vec<[SudokuValue; 9]> {
+        self.sections(self.units.boxes())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.unknowns == 0
+    }
+
+    pub fn unknowns(&self) -> usize {
+        self.unknowns
     }
 
     pub(crate) fn replace(&self, cell: &Cell, value: SudokuValue) -> SudokuProblem {
-        let mut puzzle = self.puzzle.clone();
-        puzzle[cell.index] = value;
-        SudokuProblem::new(puzzle)
+        if self.puzzle[cell.index] == SudokuValue::Unknown {
+            let mut problem = self.clone();
+            problem.place(cell.index, value);
+            problem
+        } else {
+            // Removing a value cannot be tracked incrementally by the masks, so rebuild them.
+            let mut puzzle = self.puzzle;
+            puzzle[cell.index] = value;
+            SudokuProblem::build(puzzle, self.units.clone())
+        }
+    }
+
+    pub fn value(&self, cell: &Cell) -> SudokuValue {
+        self.puzzle[cell.index]
+    }
+
+    pub fn candidates(&self, cell: &Cell) -> impl Iterator<Item = SudokuValue> {
+        let mask = self.candidates[cell.index];
+        SudokuValue::candidates()
+            .iter()
+            .enumerate()
+            .filter(move |(digit, _)| mask & (1 << digit) != 0)
+            .map(|(_, value)| *value)
+    }
+
+    pub(crate) fn candidate_mask(&self, cell: &Cell) -> u16 {
+        self.candidates[cell.index]
     }
 
     pub fn position(&self, value: &SudokuValue) -> Option<Cell> {
@@ -168,8 +524,13 @@ impl SudokuProblem {
     }
 }
 
+// The digits either side of the digit (as bitmasks).
+fn consecutive(bit: u16) -> u16 {
+    ((bit << 1) | (bit >> 1)) & ALL_CANDIDATES
+}
+
 impl SudokuValue {
-    fn to_count_index(&self) -> Option<usize> {
+    pub(crate) fn to_count_index(self) -> Option<usize> {
         match self {
             SudokuValue::Unknown => None,
             SudokuValue::One => Some(0),
@@ -188,6 +549,7 @@ impl SudokuValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::format::Format;
 
     #[test]
     fn sections() {
@@ -273,25 +635,122 @@ mod tests {
         assert!(problem.is_valid());
 
         // Row invalid
-        let problem = SudokuProblem::from_str(
-            "379300014060010070080009005435007000090040020000800436900700080040080050850000249",
-        )
-        .unwrap();
-        assert!(!problem.is_valid());
+        let sdm =
+            "379300014060010070080009005435007000090040020000800436900700080040080050850000249";
+        assert!(!as_problem(sdm).is_valid());
+        assert_eq!(
+            SudokuProblem::from_str(sdm),
+            Err(ParseError::DuplicateGiven {
+                value: SudokuValue::Three,
+                unit: Unit::Row(1),
+                first: Cell::row_column(0, 0).unwrap(),
+                second: Cell::row_column(0, 3).unwrap(),
+            })
+        );
 
         // Column invalid
-        let problem = SudokuProblem::from_str(
-            "379000014360010070080009005435007000090040020000800436900700080040080050850000249",
-        )
-        .unwrap();
-        assert!(!problem.is_valid());
+        let sdm =
+            "379000014360010070080009005435007000090040020000800436900700080040080050850000249";
+        assert!(!as_problem(sdm).is_valid());
+        assert_eq!(
+            SudokuProblem::from_str(sdm),
+            Err(ParseError::DuplicateGiven {
+                value: SudokuValue::Three,
+                unit: Unit::Column(1),
+                first: Cell::row_column(0, 0).unwrap(),
+                second: Cell::row_column(1, 0).unwrap(),
+            })
+        );
 
         // Square invalid
-        let problem = SudokuProblem::from_str(
-            "379000014063010070080009005435007000090040020000800436900700080040080050850000249",
-        )
-        .unwrap();
-        assert!(!problem.is_valid());
+        let sdm =
+            "379000014063010070080009005435007000090040020000800436900700080040080050850000249";
+        assert!(!as_problem(sdm).is_valid());
+        assert_eq!(
+            SudokuProblem::from_str(sdm),
+            Err(ParseError::DuplicateGiven {
+                value: SudokuValue::Three,
+                unit: Unit::Square(1),
+                first: Cell::row_column(0, 0).unwrap(),
+                second: Cell::row_column(1, 2).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            SudokuProblem::from_str("379000014"),
+            Err(ParseError::WrongLength {
+                expected: 81,
+                length: 9
+            })
+        );
+        assert_eq!(
+            SudokuProblem::from_str(
+                "3790000140600100700800090054350070000900400200008004369007000800400800508500002490",
+            ),
+            Err(ParseError::WrongLength {
+                expected: 81,
+                length: 82
+            })
+        );
+        assert_eq!(
+            SudokuProblem::from_str(
+                "379000014060010070080009005435007000090040020000800436900700080040080050850000x49",
+            ),
+            Err(ParseError::InvalidCharacter {
+                position: 79,
+                character: 'x',
+            })
+        );
+    }
+
+    #[test]
+    fn display_parse_error() {
+        assert_eq!(
+            ParseError::WrongLength {
+                expected: 81,
+                length: 80
+            }
+            .to_string(),
+            "expected 81 cells, but found 80"
+        );
+        assert_eq!(
+            ParseError::WrongRowCount { rows: 8 }.to_string(),
+            "expected 9 rows, but found 8"
+        );
+        assert_eq!(
+            ParseError::WrongRowLength { row: 2, length: 10 }.to_string(),
+            "expected 9 cells in row 2, but found 10"
+        );
+        assert_eq!(
+            ParseError::InvalidSymbol {
+                position: 7,
+                character: 'h',
+                side: 16,
+            }
+            .to_string(),
+            "invalid character 'h' at position 7 (expected 0-G)"
+        );
+        assert_eq!(
+            ParseError::InvalidCharacter {
+                position: 3,
+                character: '.',
+            }
+            .to_string(),
+            "invalid character '.' at position 3 (expected 0-9)"
+        );
+        assert_eq!(
+            ParseError::DuplicateGiven {
+                value: SudokuValue::Three,
+                unit: Unit::Square(1),
+                first: Cell::row_column(0, 0).unwrap(),
+                second: Cell::row_column(1, 2).unwrap(),
+            }
+            .to_string(),
+            "duplicate 3 given in square 1 (at r1c1 and r2c3)"
+        );
     }
 
     #[test]
@@ -312,6 +771,7 @@ mod tests {
         )
         .unwrap();
         assert!(problem.is_complete());
+        assert_eq!(problem.unknowns(), 0);
 
         // Row incomplete
         let problem = SudokuProblem::from_str(
@@ -319,6 +779,7 @@ mod tests {
         )
         .unwrap();
         assert!(!problem.is_complete());
+        assert_eq!(problem.unknowns(), 1);
 
         // Column incomplete
         let problem = SudokuProblem::from_str(
@@ -337,33 +798,29 @@ mod tests {
 
     #[test]
     fn replace() {
-        let problem = SudokuProblem::from_str(
+        let problem = as_problem(
             "012345678901234567890123456789012345678901234567890123456789012345678901234567890",
-        )
-        .unwrap();
+        );
 
         assert_eq!(
             problem.replace(&Cell::row_column(0, 0).unwrap(), SudokuValue::One),
-            SudokuProblem::from_str(
-                "112345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            as_problem(
+                "112345678901234567890123456789012345678901234567890123456789012345678901234567890"
             )
-            .unwrap()
         );
         assert_eq!(
             problem.replace(&Cell::row_column(0, 8).unwrap(), SudokuValue::Nine),
-            SudokuProblem::from_str(
-                "012345679901234567890123456789012345678901234567890123456789012345678901234567890",
+            as_problem(
+                "012345679901234567890123456789012345678901234567890123456789012345678901234567890"
             )
-            .unwrap()
         );
     }
 
     #[test]
     fn position() {
-        let problem = SudokuProblem::from_str(
+        let problem = as_problem(
             "012345678901234567890123456789012345678901234567890123456789012345678901234567890",
-        )
-        .unwrap();
+        );
 
         assert_eq!(
             problem.position(&SudokuValue::Unknown),
@@ -378,6 +835,120 @@ mod tests {
         assert_eq!(SudokuProblem::default().position(&SudokuValue::One), None,);
     }
 
+    #[test]
+    fn candidates() {
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+
+        // Known cells only hold their own value.
+        assert_eq!(
+            problem
+                .candidates(&Cell::row_column(0, 0).unwrap())
+                .collect::<Vec<_>>(),
+            vec![SudokuValue::Three]
+        );
+
+        // Row 0: 1, 3, 4, 7, 9.  Column 3: 7, 8.  Square 1: 1, 9.
+        assert_eq!(
+            problem
+                .candidates(&Cell::row_column(0, 3).unwrap())
+                .collect::<Vec<_>>(),
+            vec![SudokuValue::Two, SudokuValue::Five, SudokuValue::Six]
+        );
+
+        // Placing a value removes it from the candidates of its peers.
+        let problem = problem.replace(&Cell::row_column(0, 4).unwrap(), SudokuValue::Two);
+        assert_eq!(
+            problem
+                .candidates(&Cell::row_column(0, 3).unwrap())
+                .collect::<Vec<_>>(),
+            vec![SudokuValue::Five, SudokuValue::Six]
+        );
+    }
+
+    #[test]
+    fn with_variant() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "300000000000000000000000000000000000000040000000000000000000000000000000000000000",
+        )
+        .unwrap();
+
+        // Execute
+        let x = problem.with_variant(Variant::X).unwrap();
+
+        // Verify
+        assert_eq!(x.regions(), Variant::X.regions());
+        // The 4 of r5c5 is used in both diagonals, and the 3 of r1c1 in the main diagonal (the first extra region).
+        assert_eq!(x.used.len(), 29);
+        assert_eq!(x.used[27..], [0b1100, 0b1000]);
+        // r1c1 holds a 3 on the main diagonal, so r7c7 may no longer hold a 3.
+        let cell = Cell::row_column(6, 6).unwrap();
+        assert!(problem
+            .candidates(&cell)
+            .any(|value| value == SudokuValue::Three));
+        assert!(!x.candidates(&cell).any(|value| value == SudokuValue::Three));
+        assert!(problem.replace(&cell, SudokuValue::Three).is_valid());
+        assert!(!x.replace(&cell, SudokuValue::Three).is_valid());
+        assert_ne!(x, problem);
+
+        // The extra regions are kept when rebuilding the masks.
+        let overwritten = x.replace(&Cell::row_column(0, 0).unwrap(), SudokuValue::Four);
+        // r5c5 holds a 4 on both diagonals.
+        assert!(!overwritten.is_valid());
+        assert_eq!(overwritten.regions(), Variant::X.regions());
+    }
+
+    #[test]
+    fn with_variant_duplicate() {
+        // r1c1 & r9c9 share the main diagonal.
+        let problem = SudokuProblem::from_str(
+            "100000000000000000000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        assert_eq!(
+            problem.with_variant(Variant::X),
+            Err(ParseError::DuplicateGiven {
+                value: SudokuValue::One,
+                unit: Unit::Region(1),
+                first: Cell::row_column(0, 0).unwrap(),
+                second: Cell::row_column(8, 8).unwrap(),
+            })
+        );
+        assert_eq!(
+            problem.with_variant(Variant::X).unwrap_err().to_string(),
+            "duplicate 1 given in extra region 1 (at r1c1 and r9c9)"
+        );
+        problem.with_variant(Variant::Hyper).unwrap();
+    }
+
+    #[test]
+    fn replace_maintains_masks() {
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+
+        // Introduce a conflict, then remove it again.
+        let invalid = problem.replace(&Cell::row_column(0, 3).unwrap(), SudokuValue::Three);
+        assert!(!invalid.is_valid());
+        let valid = invalid.replace(&Cell::row_column(0, 3).unwrap(), SudokuValue::Unknown);
+        assert!(valid.is_valid());
+        assert_eq!(valid, problem);
+
+        // Overwriting a known value rebuilds to the same state as parsing it directly.
+        let overwritten = problem.replace(&Cell::row_column(0, 0).unwrap(), SudokuValue::Two);
+        assert_eq!(
+            overwritten,
+            SudokuProblem::from_str(
+                "279000014060010070080009005435007000090040020000800436900700080040080050850000249",
+            )
+            .unwrap()
+        );
+    }
+
     fn as_section(snippet: &str) -> [SudokuValue; 9] {
         let tmp = snippet
             .chars()
@@ -385,4 +956,175 @@ mod tests {
             .collect::<Vec<_>>();
         tmp.try_into().unwrap()
     }
+
+    // Build a problem without rejecting duplicate givens (as `from_str` does).
+    fn as_problem(sdm: &str) -> SudokuProblem {
+        let tmp = sdm
+            .chars()
+            .map(|c| SudokuValue::try_from(c).unwrap())
+            .collect::<Vec<_>>();
+        SudokuProblem::build(tmp.try_into().unwrap(), CLASSIC.clone())
+    }
+
+    #[test]
+    fn with_jigsaw() {
+        // Setup
+        let jigsaw = Jigsaw::from_str(
+            &[
+                "aaabbbccc",
+                "aabbbcccc",
+                "daaabeecc",
+                "ddabbefff",
+                "dddeeefff",
+                "dggehhfff",
+                "ddgeehiii",
+                "ggghhhiii",
+                "ggghhhiii",
+            ]
+            .concat(),
+        )
+        .unwrap();
+        let layout = SudokuProblem::default().with_jigsaw(&jigsaw).unwrap();
+        let sdm =
+            "060000850785004006830000010304070209270900000498000075007002500620030008000798000";
+
+        // Execute
+        let problem = Format::Sdm.parse_with(sdm, &layout).unwrap();
+
+        // Verify
+        // r2c2 is not in the same region as r3c1, so both may hold an 8.
+        assert_eq!(
+            SudokuProblem::from_str(sdm).unwrap_err().to_string(),
+            "duplicate 8 given in square 1 (at r2c2 and r3c1)"
+        );
+        assert!(problem.is_valid());
+        assert_eq!(problem.squares()[0][5], SudokuValue::Three);
+        assert_eq!(
+            problem.with_jigsaw(&jigsaw).unwrap().squares(),
+            problem.squares()
+        );
+        // The jigsaw is kept when adding extra regions.
+        assert_eq!(
+            problem.with_variant(Variant::X).unwrap().squares(),
+            problem.squares()
+        );
+        // r1c1 & r3c4 share only the first region of the jigsaw.
+        let mut sdm = "0".repeat(81);
+        sdm.replace_range(0..1, "5");
+        sdm.replace_range(21..22, "5");
+        assert_eq!(
+            Format::Sdm
+                .parse_with(&sdm, &layout)
+                .unwrap_err()
+                .to_string(),
+            "duplicate 5 given in jigsaw region 1 (at r1c1 and r3c4)"
+        );
+    }
+
+    #[test]
+    fn with_constraint() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "000000000000000000000000000000000000000050000000000000000000000000000000000000000",
+        )
+        .unwrap();
+
+        // Execute
+        let constrained = problem
+            .with_constraint(Constraint::AntiKnight)
+            .and_then(|problem| problem.with_constraint(Constraint::NonConsecutive))
+            .unwrap();
+
+        // Verify
+        assert_eq!(
+            constrained.constraints(),
+            [Constraint::AntiKnight, Constraint::NonConsecutive]
+        );
+        let values = |problem: &SudokuProblem, row, column| {
+            problem
+                .candidates(&Cell::row_column(row, column).unwrap())
+                .map(|value| value.to_char())
+                .collect::<String>()
+        };
+        // A knight's move from r5c5.
+        assert_eq!(values(&problem, 2, 3), "123456789");
+        assert_eq!(values(&constrained, 2, 3), "12346789");
+        assert_eq!(values(&constrained, 3, 2), "12346789");
+        // Orthogonally adjacent to r5c5.
+        assert_eq!(values(&constrained, 4, 5), "123789");
+        // The constraints are kept when adding extra regions.
+        assert_eq!(
+            constrained.with_variant(Variant::X).unwrap().constraints(),
+            constrained.constraints()
+        );
+    }
+
+    #[test]
+    fn with_constraint_broken() {
+        let problem = SudokuProblem::from_str(
+            "001000000000010000000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        assert_eq!(
+            problem.with_constraint(Constraint::AntiKnight),
+            Err(ParseError::BrokenConstraint {
+                constraint: Constraint::AntiKnight,
+                first: Cell::row_column(0, 2).unwrap(),
+                second: Cell::row_column(1, 4).unwrap(),
+            })
+        );
+        assert_eq!(
+            problem
+                .with_constraint(Constraint::AntiKnight)
+                .unwrap_err()
+                .to_string(),
+            "the givens at r1c3 and r2c5 break the anti-knight constraint"
+        );
+        assert!(problem.with_constraint(Constraint::AntiKing).is_ok());
+
+        let problem = SudokuProblem::from_str(
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000078",
+        )
+        .unwrap();
+        assert_eq!(
+            problem.with_constraint(Constraint::NonConsecutive),
+            Err(ParseError::BrokenConstraint {
+                constraint: Constraint::NonConsecutive,
+                first: Cell::row_column(8, 7).unwrap(),
+                second: Cell::row_column(8, 8).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let problem = as_problem(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        );
+        assert!(problem.conflicts().is_empty());
+
+        let invalid = problem.replace(&Cell::row_column(0, 3).unwrap(), SudokuValue::Nine);
+        assert_eq!(
+            invalid.conflicts(),
+            [
+                Cell::row_column(0, 2).unwrap(),
+                Cell::row_column(0, 3).unwrap(),
+                Cell::row_column(2, 5).unwrap(),
+            ]
+        );
+
+        // Beside the 5 at r3c9, under the non-consecutive constraint.
+        let problem = SudokuProblem::default()
+            .with_constraint(Constraint::NonConsecutive)
+            .unwrap()
+            .replace(&Cell::row_column(2, 8).unwrap(), SudokuValue::Five)
+            .replace(&Cell::row_column(2, 7).unwrap(), SudokuValue::Four);
+        assert_eq!(
+            problem.conflicts(),
+            [
+                Cell::row_column(2, 7).unwrap(),
+                Cell::row_column(2, 8).unwrap()
+            ]
+        );
+    }
 }
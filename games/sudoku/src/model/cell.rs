@@ -1,33 +1,116 @@
 use std::convert::TryFrom;
 use std::fmt::Formatter;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub struct Cell {
     row: usize,
     column: usize,
-    pub(super) index: usize,
+    pub(crate) index: usize,
 }
 
 impl Cell {
+    #[allow(clippy::result_unit_err)]
     pub fn row_column(row: usize, column: usize) -> Result<Cell, ()> {
         if row < 9 && column < 9 {
-            let index = (&row * 9) + &column;
+            let index = (row * 9) + column;
             Ok(Self { row, column, index })
         } else {
             Err(())
         }
     }
 
+    #[allow(clippy::result_unit_err)]
     pub fn index(index: usize) -> Result<Cell, ()> {
         if index < 9 * 9 {
-            let row = &index / 9;
-            let column = &index % 9;
+            let row = index / 9;
+            let column = index % 9;
             Ok(Self { row, column, index })
         } else {
             Err(())
         }
     }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn square(&self) -> usize {
+        ((self.row / 3) * 3) + (self.column / 3)
+    }
+}
+
+impl std::fmt::Display for Cell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "r{}c{}", self.row + 1, self.column + 1)
+    }
+}
+
+// Whether every cell can be reached from the first, by steps between orthogonally adjacent cells.
+// There is nothing to connect without any cells.
+pub(crate) fn is_connected(cells: &[Cell]) -> bool {
+    let mut reached = match cells.first() {
+        Some(first) => vec![*first],
+        None => return true,
+    };
+    let mut i = 0;
+
+    while i < reached.len() {
+        let current = reached[i];
+
+        for cell in cells.iter() {
+            let distance =
+                current.row().abs_diff(cell.row()) + current.column().abs_diff(cell.column());
+
+            if distance == 1 && !reached.contains(cell) {
+                reached.push(*cell);
+            }
+        }
+
+        i += 1;
+    }
+
+    reached.len() == cells.len()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCell(pub String);
+
+impl std::fmt::Display for InvalidCell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid cell '{}' (expected r1c1 to r9c9)", self.0)
+    }
+}
+
+impl std::error::Error for InvalidCell {}
+
+// The inverse of `Display`, case insensitive: `r3c5` is the cell in row 3, column 5.
+impl FromStr for Cell {
+    type Err = InvalidCell;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidCell(text.to_string());
+        let lower = text.to_lowercase();
+        let (row, column) = lower
+            .strip_prefix('r')
+            .and_then(|rest| rest.split_once('c'))
+            .ok_or_else(invalid)?;
+        // Only a single digit from 1 to 9 (`parse` would also accept a sign, or leading zeros).
+        let number = |text: &str| match text.as_bytes() {
+            [digit @ b'1'..=b'9'] => Some((digit - b'1') as usize),
+            _ => None,
+        };
+
+        match (number(row), number(column)) {
+            (Some(row), Some(column)) => Cell::row_column(row, column).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
 }
 
 const CANDIDATES: [SudokuValue; 9] = [
@@ -77,8 +160,19 @@ impl SudokuValue {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidValue(pub char);
+
+impl std::fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid value '{}' (expected 0-9)", self.0)
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
 impl TryFrom<char> for SudokuValue {
-    type Error = ();
+    type Error = InvalidValue;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
@@ -92,7 +186,7 @@ impl TryFrom<char> for SudokuValue {
             '7' => Ok(SudokuValue::Seven),
             '8' => Ok(SudokuValue::Eight),
             '9' => Ok(SudokuValue::Nine),
-            _ => Err(()),
+            _ => Err(InvalidValue(value)),
         }
     }
 }
@@ -104,7 +198,7 @@ impl std::fmt::Debug for SudokuValue {
 }
 
 impl SudokuValue {
-    pub(crate) fn to_char(&self) -> char {
+    pub(crate) fn to_char(self) -> char {
         match self {
             SudokuValue::Unknown => '0',
             SudokuValue::One => '1',
@@ -156,6 +250,47 @@ mod tests {
         Cell::index(9 * 9).unwrap_err();
     }
 
+    #[test]
+    fn display_cell() {
+        assert_eq!(Cell::row_column(0, 0).unwrap().to_string(), "r1c1");
+        assert_eq!(Cell::row_column(2, 4).unwrap().to_string(), "r3c5");
+        assert_eq!(Cell::index(80).unwrap().to_string(), "r9c9");
+    }
+
+    #[test]
+    fn parse_cell() {
+        assert_eq!(
+            Cell::from_str("r1c1").unwrap(),
+            Cell::row_column(0, 0).unwrap()
+        );
+        assert_eq!(
+            Cell::from_str("R3C5").unwrap(),
+            Cell::row_column(2, 4).unwrap()
+        );
+        assert_eq!(Cell::from_str("r9c9").unwrap().to_string(), "r9c9");
+
+        for text in [
+            "", "r1", "c1", "r0c1", "r1c10", "r+1c1", "r01c1", "x1c1", "r1c1c1",
+        ] {
+            assert_eq!(Cell::from_str(text), Err(InvalidCell(text.to_string())));
+        }
+    }
+
+    #[test]
+    fn connected() {
+        let cells = |texts: &[&str]| {
+            texts
+                .iter()
+                .map(|text| Cell::from_str(text).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(is_connected(&cells(&["r1c1", "r1c2", "r2c2"])));
+        assert!(is_connected(&cells(&["r5c5"])));
+        assert!(!is_connected(&cells(&["r1c1", "r2c2"])));
+        assert!(is_connected(&[]));
+    }
+
     #[test]
     fn convert_value() {
         assert_eq!(
@@ -221,6 +356,6 @@ mod tests {
 
     #[test]
     fn invalid_value() {
-        SudokuValue::try_from('a').unwrap_err();
+        assert_eq!(SudokuValue::try_from('a'), Err(InvalidValue('a')));
     }
 }
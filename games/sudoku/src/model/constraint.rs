@@ -0,0 +1,137 @@
+use crate::model::cell::Cell;
+
+const CONSTRAINTS: [Constraint; 3] = [
+    Constraint::AntiKnight,
+    Constraint::AntiKing,
+    Constraint::NonConsecutive,
+];
+const KNIGHT_MOVES: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+const KING_MOVES: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const ORTHOGONAL_MOVES: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+// A rule between the cells a given move apart, on top of the units; the constraints may be combined freely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    // No two cells a knight's move apart may hold the same digit.
+    AntiKnight,
+    // No two cells a king's move apart (including diagonally) may hold the same digit.
+    AntiKing,
+    // No two orthogonally adjacent cells may hold consecutive digits.
+    NonConsecutive,
+}
+
+impl Constraint {
+    pub fn all() -> &'static [Constraint] {
+        &CONSTRAINTS[..]
+    }
+
+    // The cells which the constraint relates to the cell.
+    pub fn cells(&self, cell: &Cell) -> Vec<Cell> {
+        let moves = match self {
+            Constraint::AntiKnight => &KNIGHT_MOVES[..],
+            Constraint::AntiKing => &KING_MOVES[..],
+            Constraint::NonConsecutive => &ORTHOGONAL_MOVES[..],
+        };
+
+        moves
+            .iter()
+            .filter_map(|(rows, columns)| {
+                let row = cell.row().checked_add_signed(*rows)?;
+                let column = cell.column().checked_add_signed(*columns)?;
+                Cell::row_column(row, column).ok()
+            })
+            .collect()
+    }
+
+    // Whether the related cells must hold different digits (or else, non-consecutive digits).
+    pub(crate) fn is_distinct(&self) -> bool {
+        match self {
+            Constraint::AntiKnight | Constraint::AntiKing => true,
+            Constraint::NonConsecutive => false,
+        }
+    }
+}
+
+impl std::str::FromStr for Constraint {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "anti-knight" | "knight" => Ok(Constraint::AntiKnight),
+            "anti-king" | "king" => Ok(Constraint::AntiKing),
+            "non-consecutive" | "nonconsecutive" => Ok(Constraint::NonConsecutive),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::AntiKnight => write!(f, "anti-knight"),
+            Constraint::AntiKing => write!(f, "anti-king"),
+            Constraint::NonConsecutive => write!(f, "non-consecutive"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn constraint_round_trip() {
+        for constraint in Constraint::all() {
+            assert_eq!(
+                Constraint::from_str(&constraint.to_string()).unwrap(),
+                *constraint
+            );
+        }
+
+        Constraint::from_str("anti-bishop").unwrap_err();
+    }
+
+    #[test]
+    fn cells() {
+        let corner = Cell::row_column(0, 0).unwrap();
+        let centre = Cell::row_column(4, 4).unwrap();
+
+        assert_eq!(
+            Constraint::AntiKnight.cells(&corner),
+            [
+                Cell::row_column(1, 2).unwrap(),
+                Cell::row_column(2, 1).unwrap()
+            ]
+        );
+        assert_eq!(Constraint::AntiKnight.cells(&centre).len(), 8);
+        assert_eq!(Constraint::AntiKing.cells(&corner).len(), 3);
+        assert_eq!(Constraint::AntiKing.cells(&centre).len(), 8);
+        assert_eq!(
+            Constraint::NonConsecutive.cells(&corner),
+            [
+                Cell::row_column(0, 1).unwrap(),
+                Cell::row_column(1, 0).unwrap()
+            ]
+        );
+        assert_eq!(Constraint::NonConsecutive.cells(&centre).len(), 4);
+    }
+}
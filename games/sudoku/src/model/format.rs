@@ -0,0 +1,331 @@
+use crate::model::board::{ParseError, SudokuProblem};
+use crate::model::cell::{Cell, SudokuValue};
+use std::convert::TryFrom;
+
+const FORMATS: [Format; 5] = [
+    Format::Sdm,
+    Format::Line,
+    Format::Grid,
+    Format::Sdk,
+    Format::Ss,
+];
+const BLANKS: [char; 3] = ['0', '.', '_'];
+// The box separators of the Simple Sudoku layout.
+const SEPARATORS: [char; 3] = ['|', '-', '+'];
+
+// The text layouts of a problem.
+// Every format reads any of `0`, `.` or `_` as a blank, and skips comment lines (starting with `#`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // A single line of 81 digits, with `0` for blanks.
+    Sdm,
+    // A single line of 81 characters, with `.` for blanks.
+    Line,
+    // 9 lines of 9 digits, with `0` for blanks.
+    Grid,
+    // 9 lines of 9 characters, with `.` for blanks.
+    Sdk,
+    // Simple Sudoku: 9 lines of 9 characters, with `.` for blanks and `|` & `-` between the boxes.
+    Ss,
+}
+
+impl Format {
+    pub fn all() -> &'static [Format] {
+        &FORMATS[..]
+    }
+
+    pub fn detect(text: &str) -> Format {
+        let lines = content_lines(text).collect::<Vec<_>>();
+        let blank_dots = lines.iter().any(|line| line.contains(['.', '_']));
+
+        if lines
+            .iter()
+            .any(|line| line.contains('|') || line.trim().chars().all(|c| SEPARATORS.contains(&c)))
+        {
+            Format::Ss
+        } else if lines.len() <= 1 {
+            if blank_dots {
+                Format::Line
+            } else {
+                Format::Sdm
+            }
+        } else if blank_dots {
+            Format::Sdk
+        } else {
+            Format::Grid
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Result<SudokuProblem, ParseError> {
+        self.parse_with(text, &SudokuProblem::default())
+    }
+
+    // Parse the values onto the units of the `layout` (its jigsaw & extra regions), ignoring the values of the `layout`.
+    // Unlike adding the regions after parsing, the givens of a jigsaw are not checked against the squares.
+    pub fn parse_with(
+        &self,
+        text: &str,
+        layout: &SudokuProblem,
+    ) -> Result<SudokuProblem, ParseError> {
+        let separators = match self {
+            Format::Ss => &SEPARATORS[..],
+            _ => &[],
+        };
+        let rows = rows(text, separators)?;
+
+        match self {
+            Format::Sdm | Format::Line => layout.with_values(rows.into_iter().flatten().collect()),
+            Format::Grid | Format::Sdk | Format::Ss => {
+                if rows.len() != 9 {
+                    return Err(ParseError::WrongRowCount { rows: rows.len() });
+                }
+
+                if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != 9) {
+                    return Err(ParseError::WrongRowLength {
+                        row: i + 1,
+                        length: row.len(),
+                    });
+                }
+
+                layout.with_values(rows.into_iter().flatten().collect())
+            }
+        }
+    }
+
+    pub fn write(&self, problem: &SudokuProblem) -> String {
+        let blank = match self {
+            Format::Sdm | Format::Grid => '0',
+            Format::Line | Format::Sdk | Format::Ss => '.',
+        };
+        let rows = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|column| {
+                        let cell = Cell::row_column(row, column).expect("must be a valid cell");
+                        match problem.value(&cell) {
+                            SudokuValue::Unknown => blank,
+                            value => value.to_char(),
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        match self {
+            Format::Sdm | Format::Line => rows.concat(),
+            Format::Grid | Format::Sdk => rows.join("\n"),
+            Format::Ss => rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    let line = format!("{}|{}|{}", &row[0..3], &row[3..6], &row[6..9]);
+
+                    if i == 3 || i == 6 {
+                        format!("-----------\n{}", line)
+                    } else {
+                        line
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "sdm" => Ok(Format::Sdm),
+            "line" => Ok(Format::Line),
+            "grid" => Ok(Format::Grid),
+            "sdk" => Ok(Format::Sdk),
+            "ss" => Ok(Format::Ss),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Sdm => write!(f, "sdm"),
+            Format::Line => write!(f, "line"),
+            Format::Grid => write!(f, "grid"),
+            Format::Sdk => write!(f, "sdk"),
+            Format::Ss => write!(f, "ss"),
+        }
+    }
+}
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+fn content_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .filter(|line| !is_comment(line) && !line.trim().is_empty())
+}
+
+// The values of each line which holds any cells.
+// An invalid character is reported by its position in the whole text (counting from 1).
+fn rows(text: &str, separators: &[char]) -> Result<Vec<Vec<SudokuValue>>, ParseError> {
+    let mut rows = Vec::default();
+    let mut position = 0;
+
+    for line in text.split('\n') {
+        let start = position;
+        position += line.chars().count() + 1;
+
+        if is_comment(line) {
+            continue;
+        }
+
+        let mut row = Vec::default();
+
+        for (i, character) in line.chars().enumerate() {
+            if character.is_whitespace() || separators.contains(&character) {
+                continue;
+            }
+
+            if BLANKS.contains(&character) {
+                row.push(SudokuValue::Unknown);
+            } else {
+                row.push(SudokuValue::try_from(character).map_err(|_| {
+                    ParseError::InvalidCharacter {
+                        position: start + i + 1,
+                        character,
+                    }
+                })?);
+            }
+        }
+
+        if !row.is_empty() {
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const SDM: &str =
+        "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+
+    #[test]
+    fn format_round_trip() {
+        let problem = SudokuProblem::from_str(SDM).unwrap();
+
+        for format in Format::all() {
+            assert_eq!(Format::from_str(&format.to_string()).unwrap(), *format);
+
+            let text = format.write(&problem);
+            assert_eq!(Format::detect(&text), *format, "{}", text);
+            assert_eq!(format.parse(&text).unwrap(), problem, "{}", format);
+        }
+    }
+
+    #[test]
+    fn write() {
+        let problem = SudokuProblem::from_str(SDM).unwrap();
+        assert_eq!(Format::Sdm.write(&problem), SDM);
+        assert_eq!(
+            Format::Line.write(&problem),
+            "379....14.6..1..7..8...9..5435..7....9..4..2....8..4369..7...8..4..8..5.85....249"
+        );
+        assert_eq!(
+            Format::Ss.write(&problem),
+            [
+                "379|...|.14",
+                ".6.|.1.|.7.",
+                ".8.|..9|..5",
+                "-----------",
+                "435|..7|...",
+                ".9.|.4.|.2.",
+                "...|8..|436",
+                "-----------",
+                "9..|7..|.8.",
+                ".4.|.8.|.5.",
+                "85.|...|249",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn parse_with_comments() {
+        // Setup
+        let text = [
+            "# A puzzle in the .sdk layout",
+            "#D from the newspaper",
+            "379...014",
+            ".6..1..7.",
+            ".8...9..5",
+            "",
+            "435..7...",
+            ".9..4..2.",
+            "...8..436",
+            "9..7...8.",
+            ".4..8..5.",
+            "85....249",
+            "",
+        ]
+        .join("\n");
+
+        // Execute
+        let format = Format::detect(&text);
+        let problem = format.parse(&text).unwrap();
+
+        // Verify
+        assert_eq!(format, Format::Sdk);
+        assert_eq!(problem, SudokuProblem::from_str(SDM).unwrap());
+    }
+
+    #[test]
+    fn parse_blanks() {
+        let text = SDM.replace('0', "_");
+        assert_eq!(Format::detect(&text), Format::Line);
+        assert_eq!(
+            Format::Line.parse(&text).unwrap(),
+            SudokuProblem::from_str(SDM).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Format::Sdk.parse("379...014\n.6..1..7."),
+            Err(ParseError::WrongRowCount { rows: 2 })
+        );
+
+        let text = Format::Sdk
+            .write(&SudokuProblem::from_str(SDM).unwrap())
+            .replacen(".6..1..7.", ".6..1..7", 1);
+        assert_eq!(
+            Format::Sdk.parse(&text),
+            Err(ParseError::WrongRowLength { row: 2, length: 8 })
+        );
+
+        // Separators are only allowed in the Simple Sudoku layout.
+        let text = Format::Ss.write(&SudokuProblem::from_str(SDM).unwrap());
+        assert_eq!(
+            Format::Sdk.parse(&text),
+            Err(ParseError::InvalidCharacter {
+                position: 4,
+                character: '|',
+            })
+        );
+        assert_eq!(
+            Format::Ss.parse(&text.replacen("7", "x", 1)),
+            Err(ParseError::InvalidCharacter {
+                position: 2,
+                character: 'x',
+            })
+        );
+    }
+}
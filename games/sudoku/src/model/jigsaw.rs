@@ -0,0 +1,168 @@
+use crate::model::cell::{is_connected, Cell};
+use std::convert::TryInto;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JigsawError {
+    WrongLength { length: usize },
+    WrongRegionCount { regions: usize },
+    // Each region is named by its character in the map.
+    WrongRegionSize { region: char, size: usize },
+    Disconnected { region: char },
+}
+
+impl std::fmt::Display for JigsawError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JigsawError::WrongLength { length } => {
+                write!(
+                    f,
+                    "expected 81 cells in the region map, but found {}",
+                    length
+                )
+            }
+            JigsawError::WrongRegionCount { regions } => {
+                write!(f, "expected 9 regions, but found {}", regions)
+            }
+            JigsawError::WrongRegionSize { region, size } => {
+                write!(
+                    f,
+                    "expected 9 cells in region '{}', but found {}",
+                    region, size
+                )
+            }
+            JigsawError::Disconnected { region } => {
+                write!(f, "region '{}' is not connected", region)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JigsawError {}
+
+// The irregular regions of a jigsaw puzzle, which take the place of the squares: 9 connected regions of 9 cells each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jigsaw {
+    regions: Vec<[Cell; 9]>,
+}
+
+impl Jigsaw {
+    // The regions, in the order their characters first appear in the map.
+    pub fn regions(&self) -> &[[Cell; 9]] {
+        &self.regions
+    }
+}
+
+// A map of 81 characters, from left to right, top to bottom, where the cells of each region share the same character.
+// Whitespace is ignored, so the map may also be laid out as 9 lines of 9 characters.
+impl FromStr for Jigsaw {
+    type Err = JigsawError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let labels = map
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .collect::<Vec<_>>();
+
+        if labels.len() != 9 * 9 {
+            return Err(JigsawError::WrongLength {
+                length: labels.len(),
+            });
+        }
+
+        let mut regions: Vec<(char, Vec<Cell>)> = Vec::default();
+
+        for (index, label) in labels.iter().enumerate() {
+            let cell = Cell::index(index).expect("must be a valid index");
+
+            match regions.iter_mut().find(|(region, _)| region == label) {
+                Some((_, cells)) => cells.push(cell),
+                None => regions.push((*label, vec![cell])),
+            }
+        }
+
+        if regions.len() != 9 {
+            return Err(JigsawError::WrongRegionCount {
+                regions: regions.len(),
+            });
+        }
+
+        regions
+            .into_iter()
+            .map(|(region, cells)| {
+                if !is_connected(&cells) {
+                    return Err(JigsawError::Disconnected { region });
+                }
+
+                cells
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| JigsawError::WrongRegionSize {
+                        region,
+                        size: cells.len(),
+                    })
+            })
+            .collect::<Result<Vec<[Cell; 9]>, JigsawError>>()
+            .map(|regions| Self { regions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        // Setup
+        let map = [
+            "aaabbbccc",
+            "aaabbbccc",
+            "aaabbbccc",
+            "dddeeefff",
+            "dddeeefff",
+            "dddeeefff",
+            "ggghhhiii",
+            "ggghhhiii",
+            "ggghhhiii",
+        ]
+        .join("\n");
+
+        // Execute
+        let jigsaw = Jigsaw::from_str(&map).unwrap();
+
+        // Verify
+        assert_eq!(jigsaw.regions().len(), 9);
+
+        for (square, region) in jigsaw.regions().iter().enumerate() {
+            assert!(region.iter().all(|cell| cell.square() == square));
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let columns = "012345678".repeat(9);
+
+        assert_eq!(
+            Jigsaw::from_str("abc"),
+            Err(JigsawError::WrongLength { length: 3 })
+        );
+        assert_eq!(
+            Jigsaw::from_str(&"0123456789".repeat(9)[..81]),
+            Err(JigsawError::WrongRegionCount { regions: 10 })
+        );
+        // Each column as a region, but with r9c9 in the region of column 8.
+        assert_eq!(
+            Jigsaw::from_str(&format!("{}7", &columns[..80])),
+            Err(JigsawError::WrongRegionSize {
+                region: '7',
+                size: 10,
+            })
+        );
+        // Each column as a region, but with the top cells of the first two swapped over.
+        assert_eq!(
+            Jigsaw::from_str(&format!("10{}", &columns[2..])),
+            Err(JigsawError::Disconnected { region: '1' })
+        );
+    }
+}
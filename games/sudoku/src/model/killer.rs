@@ -0,0 +1,509 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{is_connected, Cell, SudokuValue};
+use crate::model::puzzle::Puzzle;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// The sum of every digit in the grid: 9 units of 1 + 2 + ... + 9.
+const GRID_TOTAL: usize = 9 * 45;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CageError {
+    // The `line` counts from 1.
+    InvalidLine { line: usize },
+    InvalidSum { line: usize, sum: String },
+    InvalidCell { line: usize, cell: String },
+    // A cage without cells, by its position in the layout (counting from 1).
+    EmptyCage { cage: usize },
+    // Each cage is named by its first cell.
+    RepeatedCell { cell: Cell },
+    Disconnected { cage: Cell },
+    ImpossibleSum { cage: Cell, sum: usize, size: usize },
+    WrongTotal { total: usize },
+}
+
+impl std::fmt::Display for CageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CageError::InvalidLine { line } => {
+                write!(f, "expected 'sum: cells' on line {}", line)
+            }
+            CageError::InvalidSum { line, sum } => {
+                write!(f, "invalid sum '{}' on line {}", sum, line)
+            }
+            CageError::InvalidCell { line, cell } => write!(
+                f,
+                "invalid cell '{}' on line {} (expected r1c1 to r9c9)",
+                cell, line
+            ),
+            CageError::EmptyCage { cage } => write!(f, "cage {} has no cells", cage),
+            CageError::RepeatedCell { cell } => {
+                write!(f, "{} is in more than one cage", cell)
+            }
+            CageError::Disconnected { cage } => {
+                write!(f, "the cage at {} is not connected", cage)
+            }
+            CageError::ImpossibleSum { cage, sum, size } => write!(
+                f,
+                "the cage at {} cannot sum to {} with {} distinct digits",
+                cage, sum, size
+            ),
+            CageError::WrongTotal { total } => write!(
+                f,
+                "the cages cover the grid, but sum to {} instead of {}",
+                total, GRID_TOTAL
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CageError {}
+
+// A group of cells whose digits add up to the `sum`, without repeating.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cage {
+    sum: usize,
+    cells: Vec<Cell>,
+}
+
+impl Cage {
+    pub fn new(sum: usize, cells: Vec<Cell>) -> Cage {
+        Self { sum, cells }
+    }
+
+    pub fn sum(&self) -> usize {
+        self.sum
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+}
+
+// A valid layout of cages: each is orthogonally connected, with a sum its digits can make, and no cell is in two cages.
+// The cages need not cover the whole grid, but when they do their sums must add up to 405.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cages {
+    cages: Vec<Cage>,
+    // The index of the cage holding each cell, if any.
+    cage_of: Vec<Option<usize>>,
+}
+
+impl Cages {
+    pub fn new(cages: Vec<Cage>) -> Result<Cages, CageError> {
+        let mut cage_of = vec![None; 9 * 9];
+
+        if let Some(i) = cages.iter().position(|cage| cage.cells.is_empty()) {
+            return Err(CageError::EmptyCage { cage: i + 1 });
+        }
+
+        for (i, cage) in cages.iter().enumerate() {
+            for cell in cage.cells.iter() {
+                if cage_of[cell.index].replace(i).is_some() {
+                    return Err(CageError::RepeatedCell { cell: *cell });
+                }
+            }
+        }
+
+        for cage in cages.iter() {
+            let first = cage.cells[0];
+
+            if !is_connected(&cage.cells) {
+                return Err(CageError::Disconnected { cage: first });
+            }
+
+            if combinations(cage.cells.len(), cage.sum, 0b1_1111_1111) == 0 {
+                return Err(CageError::ImpossibleSum {
+                    cage: first,
+                    sum: cage.sum,
+                    size: cage.cells.len(),
+                });
+            }
+        }
+
+        let total = cages.iter().map(|cage| cage.sum).sum();
+
+        if cage_of.iter().all(Option::is_some) && total != GRID_TOTAL {
+            return Err(CageError::WrongTotal { total });
+        }
+
+        Ok(Self { cages, cage_of })
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    pub fn cage(&self, cell: &Cell) -> Option<&Cage> {
+        self.cage_of[cell.index].map(|i| &self.cages[i])
+    }
+}
+
+// One cage per line, as its sum and then its cells: `15: r1c1 r1c2 r2c1`.
+// Blank lines, and lines starting with '#', are skipped.
+impl FromStr for Cages {
+    type Err = CageError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut cages = Vec::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (sum, cells) = line
+                .split_once(':')
+                .ok_or(CageError::InvalidLine { line: line_number })?;
+            let sum = sum.trim();
+            let sum = sum.parse::<usize>().map_err(|_| CageError::InvalidSum {
+                line: line_number,
+                sum: sum.to_string(),
+            })?;
+            let cells = cells
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|cell| !cell.is_empty())
+                .map(|cell| {
+                    Cell::from_str(cell).map_err(|_| CageError::InvalidCell {
+                        line: line_number,
+                        cell: cell.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<Cell>, CageError>>()?;
+
+            if cells.is_empty() {
+                return Err(CageError::InvalidLine { line: line_number });
+            }
+
+            cages.push(Cage::new(sum, cells));
+        }
+
+        Cages::new(cages)
+    }
+}
+
+// The union of every set of `size` distinct digits from the `available` mask which adds up to the `sum`.
+fn combinations(size: usize, sum: usize, available: u16) -> u16 {
+    (0..=0b1_1111_1111u16)
+        .filter(|mask| mask & !available == 0 && mask.count_ones() as usize == size)
+        .filter(|mask| digit_sum(*mask) == sum)
+        .fold(0, |union, mask| union | mask)
+}
+
+// The sum of the digits in the mask (bit `d` for the digit `d + 1`).
+fn digit_sum(mask: u16) -> usize {
+    (0..9).filter(|d| mask & (1 << d) != 0).map(|d| d + 1).sum()
+}
+
+// A sudoku problem with cages, where the digits of each cage must also add up to its sum without repeating.
+// The search prunes the candidates of a cage to the digits of its remaining sum combinations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillerProblem {
+    problem: SudokuProblem,
+    cages: Arc<Cages>,
+    // The digits still possible in the unknown cells of each cage.
+    combinations: Vec<u16>,
+    valid: bool,
+}
+
+impl KillerProblem {
+    pub fn new(problem: SudokuProblem, cages: Cages) -> KillerProblem {
+        let mut killer = Self {
+            problem,
+            combinations: vec![0; cages.cages.len()],
+            cages: Arc::new(cages),
+            valid: true,
+        };
+
+        for i in 0..killer.cages.cages.len() {
+            killer.prune(i);
+        }
+
+        killer
+    }
+
+    pub fn problem(&self) -> &SudokuProblem {
+        &self.problem
+    }
+
+    pub fn cages(&self) -> &Cages {
+        &self.cages
+    }
+
+    // Recompute the combinations of the cage from its known digits & the candidates of its unknown cells.
+    fn prune(&mut self, i: usize) {
+        let cage = &self.cages.cages[i];
+        let (mut used, mut total, mut unknowns, mut possible) = (0u16, 0, 0, 0u16);
+
+        for cell in cage.cells.iter() {
+            let mask = self.problem.candidate_mask(cell);
+
+            if self.problem.value(cell) == SudokuValue::Unknown {
+                unknowns += 1;
+                possible |= mask;
+            } else {
+                if used & mask != 0 {
+                    self.valid = false;
+                }
+
+                used |= mask;
+                total += digit_sum(mask);
+            }
+        }
+
+        if total > cage.sum {
+            self.valid = false;
+            return;
+        }
+
+        self.combinations[i] = combinations(unknowns, cage.sum - total, possible & !used);
+
+        if unknowns == 0 {
+            self.valid &= total == cage.sum;
+        } else if self.combinations[i] == 0 {
+            self.valid = false;
+        }
+    }
+}
+
+impl Puzzle for KillerProblem {
+    fn cell_count(&self) -> usize {
+        self.problem.cell_count()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid && self.problem.is_valid()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.problem.is_complete()
+    }
+
+    fn is_unknown(&self, index: usize) -> bool {
+        self.problem.is_unknown(index)
+    }
+
+    fn candidate_mask(&self, index: usize) -> u32 {
+        let mask = Puzzle::candidate_mask(&self.problem, index);
+
+        match self.cages.cage_of[index] {
+            Some(i) if self.is_unknown(index) => mask & u32::from(self.combinations[i]),
+            _ => mask,
+        }
+    }
+
+    fn peers(&self, index: usize) -> &[usize] {
+        self.problem.peers(index)
+    }
+
+    fn place_digit(&self, index: usize, digit: usize) -> Self {
+        let mut killer = Self {
+            problem: self.problem.place_digit(index, digit),
+            cages: self.cages.clone(),
+            combinations: self.combinations.clone(),
+            valid: self.valid,
+        };
+
+        if let Some(i) = self.cages.cage_of[index] {
+            killer.prune(i);
+        }
+
+        killer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::search::{Branching, Search, TieBreak};
+
+    fn cell(text: &str) -> Cell {
+        Cell::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn parse_cages() {
+        // Setup
+        let text = "# A corner of the grid.\n\n3: r1c1 r1c2\n 17 : r2c1, r2c2 \n";
+
+        // Execute
+        let cages = Cages::from_str(text).unwrap();
+
+        // Verify
+        assert_eq!(
+            cages.cages(),
+            [
+                Cage::new(3, vec![cell("r1c1"), cell("r1c2")]),
+                Cage::new(17, vec![cell("r2c1"), cell("r2c2")]),
+            ]
+        );
+        assert_eq!(cages.cage(&cell("r2c2")).unwrap().sum(), 17);
+        assert_eq!(cages.cage(&cell("r3c3")), None);
+    }
+
+    #[test]
+    fn parse_cages_errors() {
+        assert_eq!(
+            Cages::from_str("3 r1c1 r1c2"),
+            Err(CageError::InvalidLine { line: 1 })
+        );
+        assert_eq!(
+            Cages::from_str("\n3:"),
+            Err(CageError::InvalidLine { line: 2 })
+        );
+        assert_eq!(
+            Cages::from_str("x: r1c1"),
+            Err(CageError::InvalidSum {
+                line: 1,
+                sum: "x".to_string(),
+            })
+        );
+        assert_eq!(
+            Cages::from_str("3: r1c1 r1c0"),
+            Err(CageError::InvalidCell {
+                line: 1,
+                cell: "r1c0".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn validate_cages() {
+        assert_eq!(
+            Cages::from_str("3: r1c1 r1c2\n9: r1c2 r1c3"),
+            Err(CageError::RepeatedCell { cell: cell("r1c2") })
+        );
+        assert_eq!(
+            Cages::new(vec![
+                Cage::new(3, vec![cell("r1c1"), cell("r1c2")]),
+                Cage::new(0, vec![])
+            ]),
+            Err(CageError::EmptyCage { cage: 2 })
+        );
+        assert_eq!(
+            Cages::from_str("3: r1c1 r2c2"),
+            Err(CageError::Disconnected { cage: cell("r1c1") })
+        );
+        assert_eq!(
+            Cages::from_str("18: r1c1 r1c2"),
+            Err(CageError::ImpossibleSum {
+                cage: cell("r1c1"),
+                sum: 18,
+                size: 2,
+            })
+        );
+        assert_eq!(
+            Cages::new(vec![Cage::new(
+                45,
+                (0..9 * 9).map(|i| Cell::index(i).unwrap()).collect()
+            )]),
+            Err(CageError::ImpossibleSum {
+                cage: cell("r1c1"),
+                sum: 45,
+                size: 81,
+            })
+        );
+        // Each row as a cage, except that the first is split into a 1 and the 8 digits which add up to 43.
+        let mut rows = (2..=9)
+            .map(|row| {
+                let cells = (1..=9).map(|column| format!("r{}c{}", row, column));
+                format!("45: {}", cells.collect::<Vec<_>>().join(" "))
+            })
+            .collect::<Vec<_>>();
+        rows.push("1: r1c1".to_string());
+        rows.push("43: r1c2 r1c3 r1c4 r1c5 r1c6 r1c7 r1c8 r1c9".to_string());
+        assert_eq!(
+            Cages::from_str(&rows.join("\n")),
+            Err(CageError::WrongTotal { total: 404 })
+        );
+    }
+
+    #[test]
+    fn sum_combinations() {
+        assert_eq!(super::combinations(2, 3, 0b1_1111_1111), 0b11);
+        assert_eq!(super::combinations(2, 10, 0b1_1111_1111), 0b1_1110_1111);
+        assert_eq!(super::combinations(3, 24, 0b1_1111_1111), 0b1_1100_0000);
+        assert_eq!(super::combinations(2, 10, 0b0_0000_1111), 0);
+        assert_eq!(super::combinations(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn prune() {
+        // Setup
+        let cages = Cages::from_str("3: r1c1 r1c2\n23: r2c1 r2c2 r2c3").unwrap();
+
+        // Execute
+        let killer = KillerProblem::new(SudokuProblem::default(), cages);
+
+        // Verify
+        assert!(killer.is_valid());
+        assert_eq!(killer.candidate_mask(0), 0b0_0000_0011);
+        // 23 is only 6 + 8 + 9.
+        assert_eq!(killer.candidate_mask(9), 0b1_1010_0000);
+        assert_eq!(killer.candidate_mask(2), 0b1_1111_1111);
+
+        let placed = killer.place_digit(0, 0);
+        assert!(placed.is_valid());
+        assert_eq!(placed.candidate_mask(1), 0b0_0000_0010);
+        assert!(!killer.place_digit(9, 6).is_valid());
+        assert!(!killer.place_digit(0, 0).place_digit(1, 0).is_valid());
+    }
+
+    #[test]
+    fn search_killer() {
+        // Setup
+        let cages = Cages::from_str(KILLER).unwrap();
+        let killer = KillerProblem::new(SudokuProblem::default(), cages);
+
+        // Execute
+        let report = Search::new(killer)
+            .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+            .search(2, &mut ())
+            .unwrap();
+
+        // Verify
+        assert_eq!(report.solutions.len(), 1);
+        assert_eq!(
+            format!("{:?}", report.solutions[0].problem()),
+            KILLER_SOLUTION
+        );
+    }
+
+    const KILLER_SOLUTION: &str =
+        "379526814564318972281479365435267198698143527712895436923754681146982753857631249";
+    // Zero givens, with 2 to 5 cells in each cage.
+    const KILLER: &str = "\
+16: r1c1 r1c2 r2c2\n\
+14: r1c3 r2c3 r3c3\n\
+9: r1c4 r2c4 r2c5\n\
+8: r1c5 r1c6\n\
+18: r1c7 r1c8 r2c7\n\
+13: r1c9 r2c8 r2c9\n\
+7: r2c1 r3c1\n\
+30: r2c6 r3c5 r3c6 r4c5\n\
+20: r3c2 r4c2 r5c2\n\
+7: r3c4 r4c4 r5c4\n\
+23: r3c7 r3c8 r3c9 r4c8\n\
+10: r4c1 r5c1\n\
+13: r4c3 r5c3\n\
+8: r4c6 r4c7\n\
+22: r4c9 r5c9 r6c9 r7c9\n\
+28: r5c5 r6c3 r6c4 r6c5 r7c5\n\
+8: r5c6 r6c6\n\
+9: r5c7 r6c7\n\
+13: r5c8 r6c8 r7c8\n\
+16: r6c1 r7c1\n\
+6: r6c2 r7c2 r7c3\n\
+25: r7c4 r8c4 r9c4 r9c5\n\
+10: r7c6 r7c7\n\
+11: r8c1 r8c2 r8c3\n\
+10: r8c5 r8c6\n\
+10: r8c7 r9c6 r9c7\n\
+21: r8c8 r8c9 r9c8 r9c9\n\
+20: r9c1 r9c2 r9c3
+";
+}
@@ -0,0 +1,146 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+
+// The pencil marks (candidate values) noted against each cell, as a bitmask (bit `d` for the count index `d`).
+#[derive(Clone, PartialEq, Eq)]
+pub struct PencilMarks {
+    marks: [u16; 9 * 9],
+}
+
+impl Default for PencilMarks {
+    fn default() -> Self {
+        Self { marks: [0; 9 * 9] }
+    }
+}
+
+impl std::fmt::Debug for PencilMarks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.marks.iter().map(|mask| {
+                (0..9)
+                    .filter(|digit| mask & (1 << digit) != 0)
+                    .map(|digit| SudokuValue::candidates()[digit].to_char())
+                    .collect::<String>()
+            }))
+            .finish()
+    }
+}
+
+impl PencilMarks {
+    // Mark every candidate of the unknown cells in the problem (known cells are left unmarked).
+    pub fn from_problem(problem: &SudokuProblem) -> Self {
+        let mut pencil_marks = PencilMarks::default();
+
+        for index in 0..9 * 9 {
+            let cell = Cell::index(index).expect("must be a valid index");
+
+            if problem.value(&cell) == SudokuValue::Unknown {
+                pencil_marks.marks[index] = problem.candidate_mask(&cell);
+            }
+        }
+
+        pencil_marks
+    }
+
+    pub fn contains(&self, cell: &Cell, value: SudokuValue) -> bool {
+        match value.to_count_index() {
+            Some(digit) => self.marks[cell.index] & (1 << digit) != 0,
+            None => false,
+        }
+    }
+
+    pub fn insert(&mut self, cell: &Cell, value: SudokuValue) {
+        if let Some(digit) = value.to_count_index() {
+            self.marks[cell.index] |= 1 << digit;
+        }
+    }
+
+    pub fn remove(&mut self, cell: &Cell, value: SudokuValue) -> bool {
+        let contained = self.contains(cell, value);
+
+        if let Some(digit) = value.to_count_index() {
+            self.marks[cell.index] &= !(1 << digit);
+        }
+
+        contained
+    }
+
+    pub fn clear(&mut self, cell: &Cell) {
+        self.marks[cell.index] = 0;
+    }
+
+    pub fn values(&self, cell: &Cell) -> impl Iterator<Item = SudokuValue> {
+        let mask = self.marks[cell.index];
+        SudokuValue::candidates()
+            .iter()
+            .enumerate()
+            .filter(move |(digit, _)| mask & (1 << digit) != 0)
+            .map(|(_, value)| *value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.iter().all(|mask| *mask == 0)
+    }
+
+    pub(crate) fn mask(&self, index: usize) -> u16 {
+        self.marks[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_problem() {
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let pencil_marks = PencilMarks::from_problem(&problem);
+
+        assert_eq!(
+            pencil_marks
+                .values(&Cell::row_column(0, 0).unwrap())
+                .collect::<Vec<_>>(),
+            vec![]
+        );
+        assert_eq!(
+            pencil_marks
+                .values(&Cell::row_column(0, 3).unwrap())
+                .collect::<Vec<_>>(),
+            vec![SudokuValue::Two, SudokuValue::Five, SudokuValue::Six]
+        );
+        assert!(PencilMarks::from_problem(&SudokuProblem::default())
+            .contains(&Cell::index(0).unwrap(), SudokuValue::Nine));
+    }
+
+    #[test]
+    fn insert_remove() {
+        let cell = Cell::row_column(4, 4).unwrap();
+        let mut pencil_marks = PencilMarks::default();
+        assert!(pencil_marks.is_empty());
+
+        pencil_marks.insert(&cell, SudokuValue::One);
+        pencil_marks.insert(&cell, SudokuValue::Seven);
+        pencil_marks.insert(&cell, SudokuValue::Unknown);
+        assert!(pencil_marks.contains(&cell, SudokuValue::One));
+        assert!(!pencil_marks.contains(&cell, SudokuValue::Two));
+        assert!(!pencil_marks.contains(&cell, SudokuValue::Unknown));
+        assert_eq!(
+            pencil_marks.values(&cell).collect::<Vec<_>>(),
+            vec![SudokuValue::One, SudokuValue::Seven]
+        );
+
+        assert!(pencil_marks.remove(&cell, SudokuValue::One));
+        assert!(!pencil_marks.remove(&cell, SudokuValue::One));
+        assert_eq!(
+            pencil_marks.values(&cell).collect::<Vec<_>>(),
+            vec![SudokuValue::Seven]
+        );
+
+        pencil_marks.clear(&cell);
+        assert!(pencil_marks.is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+
+// A grid of any size, as seen by the depth first search: its cells by index, and its digits by count index (from 0).
+pub trait Puzzle: Clone {
+    fn cell_count(&self) -> usize;
+
+    fn is_valid(&self) -> bool;
+
+    fn is_complete(&self) -> bool;
+
+    fn is_unknown(&self, index: usize) -> bool;
+
+    // The candidate digits of the cell, as a bitmask (bit `d` for the count index `d`).
+    fn candidate_mask(&self, index: usize) -> u32;
+
+    // The other cells which share a unit with the cell.
+    fn peers(&self, index: usize) -> &[usize];
+
+    // A copy of the puzzle with the digit placed into the unknown cell.
+    fn place_digit(&self, index: usize, digit: usize) -> Self;
+}
+
+impl Puzzle for SudokuProblem {
+    fn cell_count(&self) -> usize {
+        9 * 9
+    }
+
+    fn is_valid(&self) -> bool {
+        SudokuProblem::is_valid(self)
+    }
+
+    fn is_complete(&self) -> bool {
+        SudokuProblem::is_complete(self)
+    }
+
+    fn is_unknown(&self, index: usize) -> bool {
+        self.value(&as_cell(index)) == SudokuValue::Unknown
+    }
+
+    fn candidate_mask(&self, index: usize) -> u32 {
+        u32::from(SudokuProblem::candidate_mask(self, &as_cell(index)))
+    }
+
+    fn peers(&self, index: usize) -> &[usize] {
+        &self.units().peers[index][..]
+    }
+
+    fn place_digit(&self, index: usize, digit: usize) -> Self {
+        self.replace(&as_cell(index), SudokuValue::candidates()[digit])
+    }
+}
+
+fn as_cell(index: usize) -> Cell {
+    Cell::index(index).expect("must be a valid index")
+}
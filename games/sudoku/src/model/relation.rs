@@ -0,0 +1,738 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::puzzle::Puzzle;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use std::sync::Arc;
+
+const ALL_DIGITS: u16 = 0b1_1111_1111;
+const KINDS: [Kind; 4] = [
+    Kind::Thermometer,
+    Kind::Arrow,
+    Kind::WhiteDot,
+    Kind::BlackDot,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    // The digits strictly increase from the bulb, along the rest of the cells.
+    Thermometer,
+    // The digit in the circle equals the sum of the digits along the rest of the cells, which may repeat.
+    Arrow,
+    // The digits of the pair of cells are consecutive.
+    WhiteDot,
+    // One digit of the pair of cells is double the other.
+    BlackDot,
+}
+
+impl Kind {
+    pub fn all() -> &'static [Kind] {
+        &KINDS[..]
+    }
+
+    // The fewest & most cells a relation of the kind may span.
+    fn lengths(&self) -> (usize, usize) {
+        match self {
+            Kind::Thermometer => (2, 9),
+            // The circle, and at most 9 ones.
+            Kind::Arrow => (2, 10),
+            Kind::WhiteDot | Kind::BlackDot => (2, 2),
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "thermo" | "thermometer" => Ok(Kind::Thermometer),
+            "arrow" => Ok(Kind::Arrow),
+            "white" => Ok(Kind::WhiteDot),
+            "black" => Ok(Kind::BlackDot),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Thermometer => write!(f, "thermo"),
+            Kind::Arrow => write!(f, "arrow"),
+            Kind::WhiteDot => write!(f, "white"),
+            Kind::BlackDot => write!(f, "black"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelationError {
+    // The `line` counts from 1.
+    InvalidLine {
+        line: usize,
+    },
+    InvalidKind {
+        line: usize,
+        kind: String,
+    },
+    InvalidCell {
+        line: usize,
+        cell: String,
+    },
+    // A relation without cells, by its position in the layout (counting from 1).
+    EmptyRelation {
+        relation: usize,
+    },
+    // Each relation is named by its kind & first cell.
+    WrongLength {
+        kind: Kind,
+        start: Cell,
+        length: usize,
+    },
+    RepeatedCell {
+        kind: Kind,
+        cell: Cell,
+    },
+    NotAdjacent {
+        kind: Kind,
+        first: Cell,
+        second: Cell,
+    },
+}
+
+impl std::fmt::Display for RelationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelationError::InvalidLine { line } => {
+                write!(f, "expected 'kind: cells' on line {}", line)
+            }
+            RelationError::InvalidKind { line, kind } => write!(
+                f,
+                "invalid kind '{}' on line {} (expected thermo, arrow, white or black)",
+                kind, line
+            ),
+            RelationError::InvalidCell { line, cell } => write!(
+                f,
+                "invalid cell '{}' on line {} (expected r1c1 to r9c9)",
+                cell, line
+            ),
+            RelationError::EmptyRelation { relation } => {
+                write!(f, "relation {} has no cells", relation)
+            }
+            RelationError::WrongLength {
+                kind,
+                start,
+                length,
+            } => {
+                let (fewest, most) = kind.lengths();
+                write!(
+                    f,
+                    "the {} at {} has {} cells, but needs {} to {}",
+                    kind, start, length, fewest, most
+                )
+            }
+            RelationError::RepeatedCell { kind, cell } => {
+                write!(f, "the {} visits {} more than once", kind, cell)
+            }
+            RelationError::NotAdjacent {
+                kind,
+                first,
+                second,
+            } => write!(
+                f,
+                "the {} jumps from {} to {}, which are not adjacent",
+                kind, first, second
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RelationError {}
+
+// A rule between a path of cells, listed from the bulb of a thermometer or the circle of an arrow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    kind: Kind,
+    cells: Vec<Cell>,
+}
+
+impl Relation {
+    pub fn new(kind: Kind, cells: Vec<Cell>) -> Relation {
+        Self { kind, cells }
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    // Whether the digits of the cells, in order, keep to the relation.
+    pub fn holds(&self, digits: &[usize]) -> bool {
+        match (self.kind, digits) {
+            (Kind::Thermometer, _) => digits.windows(2).all(|pair| pair[0] < pair[1]),
+            (Kind::Arrow, [circle, arrow @ ..]) => *circle == arrow.iter().sum::<usize>(),
+            (Kind::WhiteDot, [first, second]) => first.abs_diff(*second) == 1,
+            (Kind::BlackDot, [first, second]) => first * 2 == *second || second * 2 == *first,
+            _ => false,
+        }
+    }
+
+    // Narrow the digits possible in each of the cells (one mask each, in order) to those which keep to the relation.
+    fn prune(&self, masks: &mut [u16]) {
+        match self.kind {
+            Kind::Thermometer => {
+                // Each cell is above the lowest digit of the cell before it, and below the highest of the cell after.
+                for i in 1..masks.len() {
+                    masks[i] &= above(masks[i - 1]);
+                }
+
+                for i in (0..masks.len() - 1).rev() {
+                    masks[i] &= below(masks[i + 1]);
+                }
+            }
+            Kind::Arrow => {
+                let (lowest, highest) =
+                    masks[1..].iter().fold((0, 0), |(lowest, highest), mask| {
+                        (lowest + least(*mask), highest + most(*mask))
+                    });
+                masks[0] &= between(lowest, highest);
+                let (circle_lowest, circle_highest) = (least(masks[0]), most(masks[0]));
+
+                for mask in masks[1..].iter_mut() {
+                    let others_lowest = lowest - least(*mask);
+                    let others_highest = highest - most(*mask);
+                    *mask &= between(
+                        circle_lowest.saturating_sub(others_highest),
+                        circle_highest.saturating_sub(others_lowest),
+                    );
+                }
+            }
+            Kind::WhiteDot => {
+                masks[0] &= consecutive(masks[1]);
+                masks[1] &= consecutive(masks[0]);
+            }
+            Kind::BlackDot => {
+                masks[0] &= double_or_half(masks[1]);
+                masks[1] &= double_or_half(masks[0]);
+            }
+        }
+    }
+}
+
+// A valid layout of relations: each spans a path of distinct, adjacent cells (orthogonally for a dot).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relations {
+    relations: Vec<Relation>,
+    // The indices of the relations spanning each cell.
+    relations_of: Vec<Vec<usize>>,
+}
+
+impl Relations {
+    pub fn new(relations: Vec<Relation>) -> Result<Relations, RelationError> {
+        let mut relations_of = vec![Vec::default(); 9 * 9];
+
+        if let Some(i) = relations
+            .iter()
+            .position(|relation| relation.cells.is_empty())
+        {
+            return Err(RelationError::EmptyRelation { relation: i + 1 });
+        }
+
+        for (i, relation) in relations.iter().enumerate() {
+            let kind = relation.kind;
+            let (fewest, most) = kind.lengths();
+
+            if !(fewest..=most).contains(&relation.cells.len()) {
+                return Err(RelationError::WrongLength {
+                    kind,
+                    start: relation.cells[0],
+                    length: relation.cells.len(),
+                });
+            }
+
+            for (j, cell) in relation.cells.iter().enumerate() {
+                if relation.cells[..j].contains(cell) {
+                    return Err(RelationError::RepeatedCell { kind, cell: *cell });
+                }
+
+                relations_of[cell.index].push(i);
+            }
+
+            for pair in relation.cells.windows(2) {
+                let (rows, columns) = (
+                    pair[0].row().abs_diff(pair[1].row()),
+                    pair[0].column().abs_diff(pair[1].column()),
+                );
+                let adjacent = match kind {
+                    Kind::Thermometer | Kind::Arrow => rows.max(columns) == 1,
+                    Kind::WhiteDot | Kind::BlackDot => rows + columns == 1,
+                };
+
+                if !adjacent {
+                    return Err(RelationError::NotAdjacent {
+                        kind,
+                        first: pair[0],
+                        second: pair[1],
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            relations,
+            relations_of,
+        })
+    }
+
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    pub fn relations_of(&self, cell: &Cell) -> impl Iterator<Item = &Relation> {
+        self.relations_of[cell.index]
+            .iter()
+            .map(move |i| &self.relations[*i])
+    }
+
+    // Whether every relation holds on the digits of the complete problem.
+    pub fn hold(&self, problem: &SudokuProblem) -> bool {
+        self.relations.iter().all(|relation| {
+            let digits = relation
+                .cells
+                .iter()
+                .filter(|cell| problem.value(cell) != SudokuValue::Unknown)
+                .map(|cell| least(problem.candidate_mask(cell)))
+                .collect::<Vec<_>>();
+            digits.len() == relation.cells.len() && relation.holds(&digits)
+        })
+    }
+}
+
+// One relation per line, as its kind and then its cells: `thermo: r1c1 r1c2 r1c3`.
+// The kinds are `thermo` (from the bulb), `arrow` (from the circle), `white` & `black` (a dot between 2 cells).
+// Blank lines, and lines starting with '#', are skipped.
+impl FromStr for Relations {
+    type Err = RelationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut relations = Vec::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (kind, cells) = line
+                .split_once(':')
+                .ok_or(RelationError::InvalidLine { line: line_number })?;
+            let kind = kind.trim();
+            let kind = Kind::from_str(kind).map_err(|_| RelationError::InvalidKind {
+                line: line_number,
+                kind: kind.to_string(),
+            })?;
+            let cells = cells
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|cell| !cell.is_empty())
+                .map(|cell| {
+                    Cell::from_str(cell).map_err(|_| RelationError::InvalidCell {
+                        line: line_number,
+                        cell: cell.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<Cell>, RelationError>>()?;
+
+            if cells.is_empty() {
+                return Err(RelationError::InvalidLine { line: line_number });
+            }
+
+            relations.push(Relation::new(kind, cells));
+        }
+
+        Relations::new(relations)
+    }
+}
+
+// The lowest digit in the mask (bit `d` for the digit `d + 1`), or 0 when it is empty.
+fn least(mask: u16) -> usize {
+    match mask {
+        0 => 0,
+        _ => mask.trailing_zeros() as usize + 1,
+    }
+}
+
+// The highest digit in the mask, or 0 when it is empty.
+fn most(mask: u16) -> usize {
+    16 - mask.leading_zeros() as usize
+}
+
+// The digits from `lowest` to `highest`, inclusive.
+fn between(lowest: usize, highest: usize) -> u16 {
+    (lowest.max(1)..=highest.min(9)).fold(0, |mask, digit| mask | 1 << (digit - 1))
+}
+
+// The digits above the lowest digit of the mask.
+fn above(mask: u16) -> u16 {
+    match mask {
+        0 => 0,
+        _ => between(least(mask) + 1, 9),
+    }
+}
+
+// The digits below the highest digit of the mask.
+fn below(mask: u16) -> u16 {
+    between(1, most(mask).saturating_sub(1))
+}
+
+// The digits one more or one less than any digit of the mask.
+fn consecutive(mask: u16) -> u16 {
+    (mask << 1 | mask >> 1) & ALL_DIGITS
+}
+
+// The digits double or half of any digit of the mask.
+fn double_or_half(mask: u16) -> u16 {
+    (1..=9)
+        .filter(|digit| mask & 1 << (digit - 1) != 0)
+        .flat_map(|digit| [digit * 2, if digit % 2 == 0 { digit / 2 } else { 0 }])
+        .filter(|digit| (1..=9).contains(digit))
+        .fold(0, |mask, digit| mask | 1 << (digit - 1))
+}
+
+// A sudoku problem with relations, which the digits along each of them must also keep to.
+// The search prunes the candidates of the cells of each relation to the digits which can still keep to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationProblem {
+    problem: SudokuProblem,
+    relations: Arc<Relations>,
+    // The digits still allowed in each cell by the relations.
+    allowed: Vec<u16>,
+    valid: bool,
+}
+
+impl RelationProblem {
+    pub fn new(problem: SudokuProblem, relations: Relations) -> RelationProblem {
+        let mut related = Self {
+            problem,
+            relations: Arc::new(relations),
+            allowed: vec![ALL_DIGITS; 9 * 9],
+            valid: true,
+        };
+        related.prune((0..related.relations.relations.len()).collect());
+        related
+    }
+
+    pub fn problem(&self) -> &SudokuProblem {
+        &self.problem
+    }
+
+    pub fn relations(&self) -> &Relations {
+        &self.relations
+    }
+
+    // Prune the relations, and any others which share the cells they narrow, until nothing changes.
+    fn prune(&mut self, mut pending: Vec<usize>) {
+        let relations = self.relations.clone();
+
+        while let Some(i) = pending.pop() {
+            let relation = &relations.relations[i];
+            let mut masks = relation
+                .cells
+                .iter()
+                .map(|cell| self.problem.candidate_mask(cell) & self.allowed[cell.index])
+                .collect::<Vec<_>>();
+            relation.prune(&mut masks);
+
+            for (cell, mask) in relation.cells.iter().zip(masks) {
+                if mask == 0 {
+                    self.valid = false;
+                    return;
+                }
+
+                if self.allowed[cell.index] & mask != self.allowed[cell.index] {
+                    self.allowed[cell.index] &= mask;
+                    let others = relations.relations_of[cell.index]
+                        .iter()
+                        .filter(|j| **j != i && !pending.contains(j))
+                        .copied()
+                        .collect::<Vec<_>>();
+                    pending.extend(others);
+                }
+            }
+        }
+    }
+}
+
+impl Puzzle for RelationProblem {
+    fn cell_count(&self) -> usize {
+        self.problem.cell_count()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid && self.problem.is_valid()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.problem.is_complete()
+    }
+
+    fn is_unknown(&self, index: usize) -> bool {
+        self.problem.is_unknown(index)
+    }
+
+    fn candidate_mask(&self, index: usize) -> u32 {
+        Puzzle::candidate_mask(&self.problem, index) & u32::from(self.allowed[index])
+    }
+
+    fn peers(&self, index: usize) -> &[usize] {
+        self.problem.peers(index)
+    }
+
+    fn place_digit(&self, index: usize, digit: usize) -> Self {
+        let mut related = Self {
+            problem: self.problem.place_digit(index, digit),
+            relations: self.relations.clone(),
+            allowed: self.allowed.clone(),
+            valid: self.valid,
+        };
+        // The placement also removes the digit from the peers, which may narrow any of the relations.
+        related.prune((0..self.relations.relations.len()).collect());
+        related
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::search::{Branching, Search, TieBreak};
+
+    fn cell(text: &str) -> Cell {
+        Cell::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn kind_round_trip() {
+        for kind in Kind::all() {
+            assert_eq!(Kind::from_str(&kind.to_string()).unwrap(), *kind);
+        }
+
+        assert_eq!(Kind::from_str("Thermometer").unwrap(), Kind::Thermometer);
+        Kind::from_str("grey").unwrap_err();
+    }
+
+    #[test]
+    fn parse_relations() {
+        // Setup
+        let text = "# A corner of the grid.\n\nthermo: r1c1 r1c2 r2c3\n arrow : r3c1, r3c2 \nwhite: r4c1 r5c1\n";
+
+        // Execute
+        let relations = Relations::from_str(text).unwrap();
+
+        // Verify
+        assert_eq!(
+            relations.relations(),
+            [
+                Relation::new(
+                    Kind::Thermometer,
+                    vec![cell("r1c1"), cell("r1c2"), cell("r2c3")]
+                ),
+                Relation::new(Kind::Arrow, vec![cell("r3c1"), cell("r3c2")]),
+                Relation::new(Kind::WhiteDot, vec![cell("r4c1"), cell("r5c1")]),
+            ]
+        );
+        assert_eq!(
+            relations
+                .relations_of(&cell("r1c2"))
+                .map(Relation::kind)
+                .collect::<Vec<_>>(),
+            [Kind::Thermometer]
+        );
+        assert_eq!(relations.relations_of(&cell("r9c9")).count(), 0);
+    }
+
+    #[test]
+    fn new_relations_errors() {
+        assert_eq!(
+            Relations::new(vec![
+                Relation::new(Kind::WhiteDot, vec![cell("r1c1"), cell("r1c2")]),
+                Relation::new(Kind::Arrow, vec![])
+            ]),
+            Err(RelationError::EmptyRelation { relation: 2 })
+        );
+        assert_eq!(
+            RelationError::EmptyRelation { relation: 2 }.to_string(),
+            "relation 2 has no cells"
+        );
+    }
+
+    #[test]
+    fn parse_relations_errors() {
+        assert_eq!(
+            Relations::from_str("thermo r1c1 r1c2"),
+            Err(RelationError::InvalidLine { line: 1 })
+        );
+        assert_eq!(
+            Relations::from_str("\narrow:"),
+            Err(RelationError::InvalidLine { line: 2 })
+        );
+        assert_eq!(
+            Relations::from_str("grey: r1c1 r1c2"),
+            Err(RelationError::InvalidKind {
+                line: 1,
+                kind: "grey".to_string(),
+            })
+        );
+        assert_eq!(
+            Relations::from_str("black: r1c1 r1c0"),
+            Err(RelationError::InvalidCell {
+                line: 1,
+                cell: "r1c0".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn validate_relations() {
+        assert_eq!(
+            Relations::from_str("thermo: r1c1"),
+            Err(RelationError::WrongLength {
+                kind: Kind::Thermometer,
+                start: cell("r1c1"),
+                length: 1,
+            })
+        );
+        assert_eq!(
+            Relations::from_str("white: r1c1 r1c2 r1c3")
+                .unwrap_err()
+                .to_string(),
+            "the white at r1c1 has 3 cells, but needs 2 to 2"
+        );
+        assert_eq!(
+            Relations::from_str("arrow: r1c1 r1c2 r2c2 r2c1 r1c1"),
+            Err(RelationError::RepeatedCell {
+                kind: Kind::Arrow,
+                cell: cell("r1c1"),
+            })
+        );
+        assert_eq!(
+            Relations::from_str("thermo: r1c1 r1c3"),
+            Err(RelationError::NotAdjacent {
+                kind: Kind::Thermometer,
+                first: cell("r1c1"),
+                second: cell("r1c3"),
+            })
+        );
+        // Dots are only between orthogonal neighbours, whereas lines may also run diagonally.
+        assert!(Relations::from_str("thermo: r1c1 r2c2").is_ok());
+        assert_eq!(
+            Relations::from_str("black: r1c1 r2c2"),
+            Err(RelationError::NotAdjacent {
+                kind: Kind::BlackDot,
+                first: cell("r1c1"),
+                second: cell("r2c2"),
+            })
+        );
+    }
+
+    #[test]
+    fn holds() {
+        let relation = |kind, length| Relation::new(kind, vec![cell("r1c1"); length]);
+
+        assert!(relation(Kind::Thermometer, 3).holds(&[1, 4, 9]));
+        assert!(!relation(Kind::Thermometer, 3).holds(&[1, 4, 4]));
+        assert!(relation(Kind::Arrow, 3).holds(&[9, 4, 5]));
+        assert!(!relation(Kind::Arrow, 3).holds(&[8, 4, 5]));
+        assert!(relation(Kind::WhiteDot, 2).holds(&[5, 4]));
+        assert!(!relation(Kind::WhiteDot, 2).holds(&[5, 3]));
+        assert!(relation(Kind::BlackDot, 2).holds(&[3, 6]));
+        assert!(relation(Kind::BlackDot, 2).holds(&[8, 4]));
+        assert!(!relation(Kind::BlackDot, 2).holds(&[3, 5]));
+    }
+
+    #[test]
+    fn prune() {
+        // Setup
+        let relations = Relations::from_str(
+            "thermo: r1c1 r1c2 r1c3\narrow: r2c1 r2c2 r2c3\nwhite: r3c1 r3c2\nblack: r4c1 r4c2",
+        )
+        .unwrap();
+
+        // Execute
+        let related = RelationProblem::new(SudokuProblem::default(), relations);
+
+        // Verify
+        assert!(related.is_valid());
+        assert_eq!(related.candidate_mask(0), 0b0_0111_1111);
+        assert_eq!(related.candidate_mask(1), 0b0_1111_1110);
+        assert_eq!(related.candidate_mask(2), 0b1_1111_1100);
+        // The bounds only count 1 + 1 along the arrow, so the circle holds at least 2, and each of the arrow at most 8.
+        assert_eq!(related.candidate_mask(9), 0b1_1111_1110);
+        assert_eq!(related.candidate_mask(10), 0b0_1111_1111);
+        // A black dot cannot hold a 5, 7 or 9, which have no double or half.
+        assert_eq!(related.candidate_mask(27), 0b0_1010_1111);
+        assert_eq!(related.candidate_mask(4), 0b1_1111_1111);
+
+        // An 8 in the middle of the thermometer leaves only a 9 at its tip.
+        let placed = related.place_digit(1, 7);
+        assert!(placed.is_valid());
+        assert_eq!(placed.candidate_mask(2), 0b1_0000_0000);
+        assert_eq!(placed.candidate_mask(0), 0b0_0111_1111);
+        // A 4 in the circle, with a 3 along the arrow, leaves only a 1.
+        let placed = related.place_digit(9, 3).place_digit(10, 2);
+        assert_eq!(placed.candidate_mask(11), 0b0_0000_0001);
+        assert_eq!(related.place_digit(18, 4).candidate_mask(19), 0b0_0010_1000);
+        assert!(!related.place_digit(0, 0).place_digit(1, 0).is_valid());
+        assert!(!related.place_digit(27, 2).place_digit(28, 4).is_valid());
+    }
+
+    #[test]
+    fn search_relations() {
+        // Setup
+        let relations = Relations::from_str(RELATIONS).unwrap();
+        let problem = SudokuProblem::from_str(
+            "000000000000000000000000000000000000000100000000000000000004081006080700000000000",
+        )
+        .unwrap();
+        let related = RelationProblem::new(problem.clone(), relations.clone());
+
+        // Execute
+        let report = Search::new(related)
+            .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+            .search(2, &mut ())
+            .unwrap();
+
+        // Verify
+        assert_eq!(report.solutions.len(), 1);
+        let solution = report.solutions[0].problem();
+        assert_eq!(format!("{:?}", solution), SOLUTION);
+        assert!(relations.hold(solution));
+        assert!(!relations.hold(&problem));
+
+        // Without the relations, the 7 givens have many solutions.
+        let report = Search::new(problem).search(2, &mut ()).unwrap();
+        assert_eq!(report.solutions.len(), 2);
+    }
+
+    const SOLUTION: &str =
+        "379526814564318972281479365435267198698143527712895436923754681146982753857631249";
+    const RELATIONS: &str = "\
+thermo: r2c5 r1c5 r1c4 r1c3\n\
+thermo: r6c2 r7c2 r7c3 r8c3 r9c3\n\
+thermo: r8c6 r9c5 r9c4 r8c4\n\
+arrow: r4c8 r3c8 r3c7\n\
+arrow: r5c2 r4c2 r4c1 r3c1\n\
+arrow: r6c5 r6c6 r7c6\n\
+white: r5c7 r6c7\n\
+white: r2c1 r2c2\n\
+white: r8c2 r9c2\n\
+black: r9c7 r9c8\n\
+black: r4c4 r5c4\n\
+black: r1c9 r2c9\n\
+black: r6c8 r6c9
+";
+}
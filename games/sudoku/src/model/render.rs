@@ -0,0 +1,368 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::pencil::PencilMarks;
+
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+// Render a problem as a 9x9 grid, with borders around the boxes.
+// When pencil marks are given, every cell is drawn as a mini 3x3 grid of its candidates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer<'a> {
+    givens: Option<&'a SudokuProblem>,
+    pencil_marks: Option<&'a PencilMarks>,
+    conflicts: &'a [Cell],
+    colour: bool,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new() -> Renderer<'a> {
+        Renderer::default()
+    }
+
+    // The original puzzle, to tell the givens from the solved cells.
+    // Without it, every known cell is drawn as a given.
+    pub fn with_givens(mut self, givens: &'a SudokuProblem) -> Self {
+        self.givens = Some(givens);
+        self
+    }
+
+    pub fn with_pencil_marks(mut self, pencil_marks: &'a PencilMarks) -> Self {
+        self.pencil_marks = Some(pencil_marks);
+        self
+    }
+
+    // The cells to highlight (in red, with colour) for breaking a rule.
+    pub fn with_conflicts(mut self, conflicts: &'a [Cell]) -> Self {
+        self.conflicts = conflicts;
+        self
+    }
+
+    // Use ANSI colours: bold givens, green solved cells and dim candidates.
+    pub fn with_colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    pub fn render(&self, problem: &SudokuProblem) -> String {
+        let (width, height) = match self.pencil_marks {
+            Some(_) => (3, 3),
+            None => (1, 1),
+        };
+        let borders = Borders::new(problem);
+        let mut lines = Vec::default();
+
+        for row in 0..=9 {
+            // Without pencil marks, the rows within a box are not spaced out.
+            if borders.wide_row(row) || height > 1 {
+                lines.push(self.border_line(&borders, row, width));
+            }
+
+            if row == 9 {
+                break;
+            }
+
+            for line in 0..height {
+                let mut text = String::default();
+
+                for column in 0..=9 {
+                    if borders.wide_column(column) {
+                        text.push(if borders.vertical(row, column) {
+                            '│'
+                        } else {
+                            ' '
+                        });
+                    }
+
+                    if column < 9 {
+                        let cell = Cell::row_column(row, column).expect("must be a valid cell");
+                        text.push(' ');
+                        text.push_str(&self.cell(problem, &cell, line));
+
+                        if borders.wide_column(column + 1) {
+                            text.push(' ');
+                        }
+                    }
+                }
+
+                lines.push(text);
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    // The line above the `row` (or below the grid for row 9), drawing the borders between the boxes.
+    fn border_line(&self, borders: &Borders, row: usize, width: usize) -> String {
+        let mut text = String::default();
+
+        for column in 0..=9 {
+            if borders.wide_column(column) {
+                text.push(borders.junction(row, column));
+            }
+
+            if column < 9 {
+                let length = width + 1 + usize::from(borders.wide_column(column + 1));
+                let segment = if borders.horizontal(row, column) {
+                    "─"
+                } else {
+                    " "
+                };
+                text.push_str(&segment.repeat(length));
+            }
+        }
+
+        text
+    }
+
+    // The text of the cell on the `line` of its mini grid (always line 0 without pencil marks).
+    fn cell(&self, problem: &SudokuProblem, cell: &Cell, line: usize) -> String {
+        let value = problem.value(cell);
+
+        match (value, self.pencil_marks) {
+            (SudokuValue::Unknown, None) => ".".to_string(),
+            (SudokuValue::Unknown, Some(pencil_marks)) => {
+                if pencil_marks.values(cell).next().is_none() {
+                    return if line == 1 { " . " } else { "   " }.to_string();
+                }
+
+                let candidates = SudokuValue::candidates()[line * 3..(line + 1) * 3]
+                    .iter()
+                    .map(|candidate| {
+                        if pencil_marks.contains(cell, *candidate) {
+                            candidate.to_char()
+                        } else {
+                            ' '
+                        }
+                    })
+                    .collect::<String>();
+                self.paint(&candidates, DIM)
+            }
+            (value, pencil_marks) => {
+                let given = self
+                    .givens
+                    .is_none_or(|givens| givens.value(cell) != SudokuValue::Unknown);
+                let style = if self.conflicts.contains(cell) {
+                    RED
+                } else if given {
+                    BOLD
+                } else {
+                    GREEN
+                };
+                let text = self.paint(&value.to_char().to_string(), style);
+
+                match pencil_marks {
+                    None => text,
+                    Some(_) if line == 1 => format!(" {} ", text),
+                    Some(_) => "   ".to_string(),
+                }
+            }
+        }
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.colour {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+// Where to draw the borders of the boxes (the squares, or the regions of a jigsaw) around the edge & between the cells.
+// Lines are numbered from 0 to 9: line `n` is before the row (or column) `n`, so lines 0 & 9 are the edges of the grid.
+struct Borders {
+    boxes: [usize; 9 * 9],
+}
+
+impl Borders {
+    fn new(problem: &SudokuProblem) -> Borders {
+        let mut boxes = [0; 9 * 9];
+
+        for (i, cells) in problem.units().boxes().iter().enumerate() {
+            for index in cells.iter() {
+                boxes[*index] = i;
+            }
+        }
+
+        Self { boxes }
+    }
+
+    // The box of the cell, or `None` beyond the edge of the grid.
+    fn box_of(&self, row: Option<usize>, column: Option<usize>) -> Option<usize> {
+        match (row, column) {
+            (Some(row), Some(column)) if row < 9 && column < 9 => {
+                Some(self.boxes[(row * 9) + column])
+            }
+            _ => None,
+        }
+    }
+
+    // Whether the vertical line between the columns is a border, within the row.
+    fn vertical(&self, row: usize, line: usize) -> bool {
+        self.box_of(Some(row), line.checked_sub(1)) != self.box_of(Some(row), Some(line))
+    }
+
+    // Whether the horizontal line between the rows is a border, within the column.
+    fn horizontal(&self, line: usize, column: usize) -> bool {
+        self.box_of(line.checked_sub(1), Some(column)) != self.box_of(Some(line), Some(column))
+    }
+
+    fn wide_column(&self, line: usize) -> bool {
+        (0..9).any(|row| self.vertical(row, line))
+    }
+
+    fn wide_row(&self, line: usize) -> bool {
+        (0..9).any(|column| self.horizontal(line, column))
+    }
+
+    // The box drawing character where the lines between the rows & the columns cross.
+    fn junction(&self, row: usize, column: usize) -> char {
+        let up = row > 0 && self.vertical(row - 1, column);
+        let down = row < 9 && self.vertical(row, column);
+        let left = column > 0 && self.horizontal(row, column - 1);
+        let right = column < 9 && self.horizontal(row, column);
+
+        match (up, down, left, right) {
+            (false, false, false, false) => ' ',
+            (true, true, false, false) => '│',
+            (false, false, true, true) => '─',
+            (false, true, false, true) => '┌',
+            (false, true, true, false) => '┐',
+            (true, false, false, true) => '└',
+            (true, false, true, false) => '┘',
+            (true, true, false, true) => '├',
+            (true, true, true, false) => '┤',
+            (false, true, true, true) => '┬',
+            (true, false, true, true) => '┴',
+            (true, true, true, true) => '┼',
+            (true, false, false, false) => '╵',
+            (false, true, false, false) => '╷',
+            (false, false, true, false) => '╴',
+            (false, false, false, true) => '╶',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::jigsaw::Jigsaw;
+    use std::str::FromStr;
+
+    const SDM: &str =
+        "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+
+    #[test]
+    fn render() {
+        let problem = SudokuProblem::from_str(SDM).unwrap();
+        assert_eq!(
+            Renderer::new().render(&problem),
+            [
+                "┌───────┬───────┬───────┐",
+                "│ 3 7 9 │ . . . │ . 1 4 │",
+                "│ . 6 . │ . 1 . │ . 7 . │",
+                "│ . 8 . │ . . 9 │ . . 5 │",
+                "├───────┼───────┼───────┤",
+                "│ 4 3 5 │ . . 7 │ . . . │",
+                "│ . 9 . │ . 4 . │ . 2 . │",
+                "│ . . . │ 8 . . │ 4 3 6 │",
+                "├───────┼───────┼───────┤",
+                "│ 9 . . │ 7 . . │ . 8 . │",
+                "│ . 4 . │ . 8 . │ . 5 . │",
+                "│ 8 5 . │ . . . │ 2 4 9 │",
+                "└───────┴───────┴───────┘",
+            ]
+            .join("\n")
+        );
+        assert_eq!(problem.to_string(), Renderer::new().render(&problem));
+    }
+
+    #[test]
+    fn render_colour() {
+        // Setup
+        let problem = SudokuProblem::from_str(SDM).unwrap();
+        let solution = problem.replace(&Cell::row_column(0, 3).unwrap(), SudokuValue::Five);
+
+        // Execute
+        let text = Renderer::new()
+            .with_givens(&problem)
+            .with_colour(true)
+            .render(&solution);
+
+        // Verify
+        let line = text.lines().nth(1).unwrap();
+        assert_eq!(
+            line,
+            "│ \x1b[1m3\x1b[0m \x1b[1m7\x1b[0m \x1b[1m9\x1b[0m │ \x1b[32m5\x1b[0m . . │ . \x1b[1m1\x1b[0m \x1b[1m4\x1b[0m │"
+        );
+    }
+
+    #[test]
+    fn render_pencil_marks() {
+        // Setup
+        let problem = SudokuProblem::from_str(SDM).unwrap();
+        let pencil_marks = PencilMarks::from_problem(&problem);
+
+        // Execute
+        let text = Renderer::new()
+            .with_pencil_marks(&pencil_marks)
+            .render(&problem);
+
+        // Verify
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 9 * 3 + 6 + 4);
+        assert_eq!(
+            lines[1..5],
+            [
+                "│             │  2   2   2  │             │",
+                "│  3   7   9  │  56  56  56 │   6  1   4  │",
+                "│             │          8  │  8          │",
+                "│             │             │             │",
+            ]
+        );
+    }
+
+    #[test]
+    fn render_jigsaw() {
+        // Setup
+        let jigsaw = Jigsaw::from_str(
+            &[
+                "aaabbbccc",
+                "aabbbcccc",
+                "daaabeecc",
+                "ddabbefff",
+                "dddeeefff",
+                "dggehhfff",
+                "ddgeehiii",
+                "ggghhhiii",
+                "ggghhhiii",
+            ]
+            .concat(),
+        )
+        .unwrap();
+        let problem = SudokuProblem::default().with_jigsaw(&jigsaw).unwrap();
+
+        // Execute
+        let text = Renderer::new().render(&problem);
+
+        // Verify
+        // Every gap between the columns has a border in some row, except the one between the last two columns.
+        assert_eq!(
+            text.lines().take(6).collect::<Vec<_>>(),
+            [
+                "┌───────────┬───────────┬─────────┐",
+                "│ .   .   . │ .   .   . │ .   . . │",
+                "│       ┌───┘       ┌───┘         │",
+                "│ .   . │ .   .   . │ .   .   . . │",
+                "├───┐   └───────┐   ├───────┐     │",
+                "│ . │ .   .   . │ . │ .   . │ . . │",
+            ]
+        );
+        // Nor is there a border between the last two rows.
+        assert_eq!(text.lines().count(), 18);
+    }
+}
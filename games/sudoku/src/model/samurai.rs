@@ -0,0 +1,438 @@
+use crate::model::board::{ParseError, SudokuProblem};
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::puzzle::Puzzle;
+use crate::model::unit::CLASSIC;
+use lazy_static::lazy_static;
+use std::convert::TryFrom;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+// The side of the square layout which holds the 5 grids.
+pub const SIDE: usize = 21;
+// The row & column of the top left cell of each grid.
+const ORIGINS: [(usize, usize); 5] = [(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+const NAMES: [&str; 5] = [
+    "top-left",
+    "top-right",
+    "centre",
+    "bottom-left",
+    "bottom-right",
+];
+const BLANKS: [char; 3] = ['0', '.', '_'];
+
+lazy_static! {
+    // The grid & cell within it of each position in the layout: none in the gaps between the corner grids,
+    // two in the boxes which a corner grid shares with the centre, and one elsewhere.
+    static ref GRID_CELLS: Vec<Vec<(usize, Cell)>> = (0..SIDE * SIDE)
+        .map(|position| {
+            let (row, column) = (position / SIDE, position % SIDE);
+            ORIGINS
+                .iter()
+                .enumerate()
+                .filter_map(|(grid, (top, left))| {
+                    let cell = Cell::row_column(row.checked_sub(*top)?, column.checked_sub(*left)?);
+                    cell.ok().map(|cell| (grid, cell))
+                })
+                .collect()
+        })
+        .collect();
+    // The positions which share a unit with each position, in any of its grids.
+    static ref PEERS: Vec<Vec<usize>> = (0..SIDE * SIDE)
+        .map(|index| {
+            let mut peers = GRID_CELLS[index]
+                .iter()
+                .flat_map(|(grid, cell)| {
+                    CLASSIC.peers[cell.index].iter().map(move |peer| position(*grid, *peer))
+                })
+                .collect::<Vec<_>>();
+            peers.sort_unstable();
+            peers.dedup();
+            peers
+        })
+        .collect();
+}
+
+// The position in the layout of the cell `index` of the `grid`.
+pub(crate) fn position(grid: usize, index: usize) -> usize {
+    let (top, left) = ORIGINS[grid];
+    (top + index / 9) * SIDE + left + index % 9
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SamuraiError {
+    WrongRowCount {
+        rows: usize,
+    },
+    // The `row` & `column` count from 1.
+    InvalidCharacter {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+    // The givens of a grid repeat a digit.
+    InvalidGrid {
+        grid: usize,
+        error: ParseError,
+    },
+}
+
+impl std::fmt::Display for SamuraiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SamuraiError::WrongRowCount { rows } => {
+                write!(f, "expected {} rows, but found {}", SIDE, rows)
+            }
+            SamuraiError::InvalidCharacter {
+                row,
+                column,
+                character,
+            } => {
+                let expected = if GRID_CELLS[(row - 1) * SIDE + column - 1].is_empty() {
+                    "a space between the grids"
+                } else {
+                    "0-9"
+                };
+                write!(
+                    f,
+                    "invalid character '{}' at row {} column {} (expected {})",
+                    character, row, column, expected
+                )
+            }
+            SamuraiError::InvalidGrid { grid, error } => {
+                write!(f, "the {} grid is invalid: {}", NAMES[*grid], error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SamuraiError {}
+
+// Five 9x9 grids, each a sudoku of its own, laid out in a 21x21 square: the 4 corner grids each share a box with the centre one.
+// The shared boxes hold the same digits in both of their grids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamuraiProblem {
+    // In the order of `ORIGINS`: top left, top right, centre, bottom left, bottom right.
+    grids: Vec<SudokuProblem>,
+}
+
+impl Default for SamuraiProblem {
+    fn default() -> Self {
+        Self {
+            grids: vec![SudokuProblem::default(); ORIGINS.len()],
+        }
+    }
+}
+
+impl SamuraiProblem {
+    pub fn grids(&self) -> &[SudokuProblem] {
+        &self.grids
+    }
+
+    // The value at the `row` & `column` of the layout, or `None` in the gaps between the grids.
+    pub fn value(&self, row: usize, column: usize) -> Option<SudokuValue> {
+        GRID_CELLS[row * SIDE + column]
+            .first()
+            .map(|(grid, cell)| self.grids[*grid].value(cell))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.grids.iter().all(SudokuProblem::is_valid)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.grids.iter().all(SudokuProblem::is_complete)
+    }
+
+    // The cells of the layout which are in none of the grids.
+    pub(crate) fn is_gap(position: usize) -> bool {
+        GRID_CELLS[position].is_empty()
+    }
+
+    // The candidates of the position in all of its grids, or 0 in a gap.
+    pub(crate) fn candidates(&self, position: usize) -> u16 {
+        match GRID_CELLS[position].as_slice() {
+            [] => 0,
+            cells => cells.iter().fold(0b1_1111_1111, |mask, (grid, cell)| {
+                mask & self.grids[*grid].candidate_mask(cell)
+            }),
+        }
+    }
+
+    // Place the value into the position of each of its grids, which eliminates it from their peers.
+    pub(crate) fn place(&mut self, position: usize, value: SudokuValue) {
+        for (grid, cell) in GRID_CELLS[position].iter() {
+            self.grids[*grid] = self.grids[*grid].replace(cell, value);
+        }
+    }
+}
+
+// The 21 rows of the layout, where each of the grids' cells is a digit or a blank (any of `0`, `.` or `_`).
+// The gaps between the corner grids are spaces, which may be left off the end of a row.
+// Blank lines, and lines starting with '#', are skipped.
+impl FromStr for SamuraiProblem {
+    type Err = SamuraiError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rows = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<_>>();
+
+        if rows.len() != SIDE {
+            return Err(SamuraiError::WrongRowCount { rows: rows.len() });
+        }
+
+        let mut values = vec![vec![SudokuValue::Unknown; 9 * 9]; ORIGINS.len()];
+
+        for (row, line) in rows.iter().enumerate() {
+            let mut characters = line.chars().chain(std::iter::repeat(' '));
+
+            for column in 0..SIDE {
+                let character = characters.next().expect("must be padded with spaces");
+                let invalid = SamuraiError::InvalidCharacter {
+                    row: row + 1,
+                    column: column + 1,
+                    character,
+                };
+                let position = row * SIDE + column;
+
+                if Self::is_gap(position) {
+                    if character != ' ' {
+                        return Err(invalid);
+                    }
+
+                    continue;
+                }
+
+                let value = if BLANKS.contains(&character) {
+                    SudokuValue::Unknown
+                } else {
+                    SudokuValue::try_from(character).map_err(|_| invalid)?
+                };
+
+                for (grid, cell) in GRID_CELLS[position].iter() {
+                    values[*grid][cell.index] = value;
+                }
+            }
+
+            if line.chars().count() > SIDE {
+                return Err(SamuraiError::InvalidCharacter {
+                    row: row + 1,
+                    column: SIDE + 1,
+                    character: line.chars().nth(SIDE).expect("must be longer"),
+                });
+            }
+        }
+
+        let grids = values
+            .into_iter()
+            .enumerate()
+            .map(|(grid, values)| {
+                SudokuProblem::from_values(values)
+                    .map_err(|error| SamuraiError::InvalidGrid { grid, error })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { grids })
+    }
+}
+
+// The layout as 21 rows, with '.' for the unknown cells and spaces in the gaps.
+impl std::fmt::Display for SamuraiProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows = (0..SIDE)
+            .map(|row| {
+                let line = (0..SIDE)
+                    .map(|column| match self.value(row, column) {
+                        None => ' ',
+                        Some(SudokuValue::Unknown) => '.',
+                        Some(value) => value.to_char(),
+                    })
+                    .collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+// The positions of the 21x21 layout, where those in the gaps are never unknown.
+impl Puzzle for SamuraiProblem {
+    fn cell_count(&self) -> usize {
+        SIDE * SIDE
+    }
+
+    fn is_valid(&self) -> bool {
+        SamuraiProblem::is_valid(self)
+    }
+
+    fn is_complete(&self) -> bool {
+        SamuraiProblem::is_complete(self)
+    }
+
+    fn is_unknown(&self, index: usize) -> bool {
+        GRID_CELLS[index]
+            .first()
+            .is_some_and(|(grid, cell)| self.grids[*grid].value(cell) == SudokuValue::Unknown)
+    }
+
+    fn candidate_mask(&self, index: usize) -> u32 {
+        u32::from(self.candidates(index))
+    }
+
+    fn peers(&self, index: usize) -> &[usize] {
+        &PEERS[index]
+    }
+
+    fn place_digit(&self, index: usize, digit: usize) -> Self {
+        let mut problem = self.clone();
+        problem.place(index, SudokuValue::candidates()[digit]);
+        problem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::board::Unit;
+
+    #[test]
+    fn parse() {
+        // Setup
+        let mut rows = vec![".".repeat(9) + "   " + &".".repeat(9); 6];
+        rows.push("123456789".to_string() + &".".repeat(12));
+        rows.extend(vec![".".repeat(SIDE); 2]);
+        rows.extend(vec![" ".repeat(6) + &".".repeat(9); 3]);
+        rows.extend(vec![".".repeat(SIDE); 3]);
+        rows.extend(vec![".".repeat(9) + "   " + &".".repeat(9); 6]);
+        let text = format!("# The 7th row.\n{}\n", rows.join("\n"));
+
+        // Execute
+        let problem = SamuraiProblem::from_str(&text).unwrap();
+
+        // Verify
+        assert_eq!(problem.to_string(), rows.join("\n"));
+        assert_eq!(problem.value(6, 6), Some(SudokuValue::Seven));
+        assert_eq!(problem.value(6, 9), Some(SudokuValue::Unknown));
+        assert_eq!(problem.value(9, 0), None);
+        // The row of the top left grid runs on into the shared box of the centre grid.
+        let (top_left, centre) = (&problem.grids()[0], &problem.grids()[2]);
+        assert_eq!(
+            top_left.value(&Cell::row_column(6, 8).unwrap()),
+            SudokuValue::Nine
+        );
+        assert_eq!(
+            centre.value(&Cell::row_column(0, 2).unwrap()),
+            SudokuValue::Nine
+        );
+        assert_eq!(problem.grids()[1].unknowns(), 9 * 9);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let rows = vec![".".repeat(SIDE); SIDE];
+        let with_row = |row: usize, text: &str| {
+            let mut rows = rows.clone();
+            rows[row] = text.to_string();
+            rows.join("\n")
+        };
+
+        assert_eq!(
+            SamuraiProblem::from_str(&rows[1..].join("\n")),
+            Err(SamuraiError::WrongRowCount { rows: 20 })
+        );
+        let error = SamuraiProblem::from_str(&with_row(0, &".".repeat(SIDE))).unwrap_err();
+        assert_eq!(
+            error,
+            SamuraiError::InvalidCharacter {
+                row: 1,
+                column: 10,
+                character: '.',
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "invalid character '.' at row 1 column 10 (expected a space between the grids)"
+        );
+        let gaps = ".........   .........";
+        let error =
+            SamuraiProblem::from_str(&with_row(0, &gaps.replace("   ", "   x"))).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid character 'x' at row 1 column 13 (expected 0-9)"
+        );
+        // Too short, so the last cell of the top right grid is a space.
+        let mut text = vec![gaps.to_string(); 6];
+        text[5].pop();
+        assert_matches!(
+            SamuraiProblem::from_str(&(text.join("\n") + "\n" + &rows[6..].join("\n"))),
+            Err(SamuraiError::InvalidCharacter {
+                row: 6,
+                column: 21,
+                character: ' ',
+            })
+        );
+    }
+
+    #[test]
+    fn parse_duplicate() {
+        // Setup
+        let mut rows = vec![".".repeat(9) + "   " + &".".repeat(9); 6];
+        rows.extend(vec![".".repeat(SIDE); 3]);
+        rows.extend(vec![" ".repeat(6) + &".".repeat(9); 3]);
+        rows.extend(vec![".".repeat(SIDE); 3]);
+        rows.extend(vec![".".repeat(9) + "   " + &".".repeat(9); 6]);
+        // Only the centre grid holds both of the 5s.
+        rows[9] = "      5.......5".to_string();
+
+        // Execute
+        let error = SamuraiProblem::from_str(&rows.join("\n")).unwrap_err();
+
+        // Verify
+        assert_eq!(
+            error,
+            SamuraiError::InvalidGrid {
+                grid: 2,
+                error: ParseError::DuplicateGiven {
+                    value: SudokuValue::Five,
+                    unit: Unit::Row(4),
+                    first: Cell::row_column(3, 0).unwrap(),
+                    second: Cell::row_column(3, 8).unwrap(),
+                },
+            }
+        );
+        assert!(error
+            .to_string()
+            .starts_with("the centre grid is invalid: "));
+    }
+
+    #[test]
+    fn shared_boxes() {
+        // Setup
+        let problem = SamuraiProblem::default();
+        // The top left cell of the centre grid, in the bottom right box of the top left grid.
+        let shared = 6 * SIDE + 6;
+
+        // Execute
+        let placed = problem.place_digit(shared, 4);
+
+        // Verify
+        assert_eq!(problem.cell_count(), 441);
+        assert!(!problem.is_unknown(9 * SIDE));
+        assert_eq!(problem.candidate_mask(9 * SIDE), 0);
+        // Its peers in both grids, less the 8 other cells of the box which they share.
+        assert_eq!(problem.peers(shared).len(), 20 + 20 - 8);
+        assert_eq!(problem.peers(0).len(), 20);
+        assert!(!placed.is_unknown(shared));
+        // Eliminated from the row of the top left grid, and the column of the centre grid.
+        assert_eq!(placed.candidate_mask(6 * SIDE), 0b1_1110_1111);
+        assert_eq!(placed.candidate_mask(14 * SIDE + 6), 0b1_1110_1111);
+        assert_eq!(placed.candidate_mask(6 * SIDE + 14), 0b1_1110_1111);
+        assert_eq!(placed.candidate_mask(12 * SIDE + 6), 0b1_1110_1111);
+        assert_eq!(placed.candidate_mask(13 * SIDE + 7), 0b1_1111_1111);
+        assert_eq!(placed.grids()[0].unknowns(), 80);
+        assert_eq!(placed.grids()[2].unknowns(), 80);
+        assert_eq!(placed.grids()[4].unknowns(), 81);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::model::board::{ParseError, Unit};
+use crate::model::puzzle::Puzzle;
+use crate::model::unit::{layout, Layout, MAX_BOX_SIZE};
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+// The symbols of the values from 1 up to 25, in the alphanumeric encoding of the larger grids (16x16 uses 1-9 & A-G).
+const SYMBOLS: [char; 25] = [
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J',
+    'K', 'L', 'M', 'N', 'O', 'P',
+];
+const BLANKS: [char; 3] = ['0', '.', '_'];
+
+// The symbol of the `value` (counting from 1).
+pub(crate) fn symbol(value: usize) -> char {
+    SYMBOLS[value - 1]
+}
+
+// A problem of N² x N² cells, split into boxes of N x N cells: 4x4 (N = 2), 9x9 (N = 3), 16x16 (N = 4) or 25x25 (N = 5).
+// Unlike `SudokuProblem`, the values are plain numbers: 0 for unknown, or else from 1 up to N².
+// This is a separate model from `SudokuProblem`, only solved by `Search` (through `Puzzle`): none of the other solvers,
+// formats, variants, grading or generation apply to it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SizedProblem<const N: usize> {
+    values: Vec<u8>,
+    // As in `SudokuProblem`, the candidate digits of each cell and the digits used in each unit, as bitmasks.
+    candidates: Vec<u32>,
+    used: Vec<u32>,
+    unknowns: usize,
+    valid: bool,
+}
+
+impl<const N: usize> Default for SizedProblem<N> {
+    fn default() -> Self {
+        // Fails to compile for an unsupported box size.
+        let () = Self::SUPPORTED;
+        let cells = Self::SIDE * Self::SIDE;
+
+        Self {
+            values: vec![0; cells],
+            candidates: vec![(1 << Self::SIDE) - 1; cells],
+            used: vec![0; 3 * Self::SIDE],
+            unknowns: cells,
+            valid: true,
+        }
+    }
+}
+
+impl<const N: usize> FromStr for SizedProblem<N> {
+    type Err = ParseError;
+
+    // The cells from left to right, top to bottom, ignoring any whitespace.
+    // Any of `0`, `.` or `_` is a blank, and the symbols are case insensitive.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut problem = SizedProblem::default();
+        let mut index = 0;
+
+        for (i, character) in text.chars().enumerate() {
+            if character.is_whitespace() {
+                continue;
+            }
+
+            let value = if BLANKS.contains(&character) {
+                0
+            } else {
+                SYMBOLS[..Self::SIDE]
+                    .iter()
+                    .position(|symbol| *symbol == character.to_ascii_uppercase())
+                    .map(|digit| digit + 1)
+                    .ok_or(ParseError::InvalidSymbol {
+                        position: i + 1,
+                        character,
+                        side: Self::SIDE,
+                    })?
+            };
+
+            if index < problem.values.len() {
+                if let Some(error) = problem.duplicate(index, value) {
+                    return Err(error);
+                }
+
+                problem.place(index, value);
+            }
+
+            index += 1;
+        }
+
+        if index != problem.values.len() {
+            return Err(ParseError::WrongLength {
+                expected: problem.values.len(),
+                length: index,
+            });
+        }
+
+        Ok(problem)
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for SizedProblem<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.values
+                .iter()
+                .map(|value| match value {
+                    0 => '.',
+                    value => symbol(*value as usize),
+                })
+                .collect::<String>()
+        )
+    }
+}
+
+// The rows, with a space between the boxes and a blank line between the bands.
+impl<const N: usize> std::fmt::Display for SizedProblem<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = format!("{:?}", self).chars().collect::<Vec<_>>();
+        let rows = text
+            .chunks(Self::SIDE)
+            .map(|row| {
+                row.chunks(N)
+                    .map(|cells| cells.iter().collect::<String>())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>();
+        let bands = rows
+            .chunks(N)
+            .map(|band| band.join("\n"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", bands.join("\n\n"))
+    }
+}
+
+impl<const N: usize> SizedProblem<N> {
+    // The number of cells along each row, column and box.
+    pub const SIDE: usize = N * N;
+
+    const SUPPORTED: () = assert!(
+        N >= 2 && N <= MAX_BOX_SIZE,
+        "the box size must be from 2 to 5"
+    );
+
+    fn layout() -> &'static Layout {
+        layout(N)
+    }
+
+    // Place a value into an unknown cell, updating the candidate & used masks incrementally.
+    fn place(&mut self, index: usize, value: usize) {
+        debug_assert!(self.values[index] == 0);
+
+        if value == 0 {
+            return;
+        }
+
+        let bit = 1 << (value - 1);
+        self.values[index] = value as u8;
+        self.candidates[index] = bit;
+        self.unknowns -= 1;
+
+        for unit in Self::layout().cell_units[index].iter() {
+            if self.used[*unit] & bit != 0 {
+                self.valid = false;
+            }
+
+            self.used[*unit] |= bit;
+        }
+
+        for peer in Self::layout().peers[index].iter() {
+            if self.values[*peer] == 0 {
+                self.candidates[*peer] &= !bit;
+            }
+        }
+    }
+
+    // The error for placing the value into the cell, when an earlier cell in one of its units already holds it.
+    fn duplicate(&self, index: usize, value: usize) -> Option<ParseError> {
+        let layout = Self::layout();
+        let peer = *layout.peers[index]
+            .iter()
+            .find(|peer| value != 0 && self.values[**peer] as usize == value)?;
+        let unit = *layout.cell_units[index]
+            .iter()
+            .find(|unit| layout.cell_units[peer].contains(unit))
+            .expect("peers must share a unit");
+        let position = |index: usize| ((index / Self::SIDE) + 1, (index % Self::SIDE) + 1);
+
+        // As with `UNITS`, the rows come first, then the columns, then the boxes.
+        let number = (unit % Self::SIDE) + 1;
+        let unit = match unit / Self::SIDE {
+            0 => Unit::Row(number),
+            1 => Unit::Column(number),
+            _ => Unit::Square(number),
+        };
+
+        Some(ParseError::DuplicateSymbol {
+            symbol: symbol(value),
+            unit,
+            first: position(peer),
+            second: position(index),
+        })
+    }
+
+    // The value of the cell: 0 for unknown, or else from 1 up to N².
+    pub fn value(&self, row: usize, column: usize) -> usize {
+        self.values[(row * Self::SIDE) + column] as usize
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.unknowns == 0
+    }
+
+    pub fn unknowns(&self) -> usize {
+        self.unknowns
+    }
+}
+
+impl<const N: usize> Puzzle for SizedProblem<N> {
+    fn cell_count(&self) -> usize {
+        self.values.len()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn is_complete(&self) -> bool {
+        self.unknowns == 0
+    }
+
+    fn is_unknown(&self, index: usize) -> bool {
+        self.values[index] == 0
+    }
+
+    fn candidate_mask(&self, index: usize) -> u32 {
+        self.candidates[index]
+    }
+
+    fn peers(&self, index: usize) -> &[usize] {
+        &Self::layout().peers[index][..]
+    }
+
+    fn place_digit(&self, index: usize, digit: usize) -> Self {
+        let mut problem = self.clone();
+        problem.place(index, digit + 1);
+        problem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A complete grid, by shifting each row of the pattern along.
+    fn pattern<const N: usize>() -> SizedProblem<N> {
+        let side = N * N;
+        let text = (0..side * side)
+            .map(|index| {
+                let (row, column) = (index / side, index % side);
+                symbol((((N * (row % N)) + (row / N) + column) % side) + 1)
+            })
+            .collect::<String>();
+        SizedProblem::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        // Setup
+        let text = "12 34\n34 12\n\n21 4.\n43 _1";
+
+        // Execute
+        let problem = SizedProblem::<2>::from_str(text).unwrap();
+
+        // Verify
+        assert_eq!(problem.value(0, 0), 1);
+        assert_eq!(problem.value(2, 3), 0);
+        assert_eq!(problem.value(3, 2), 0);
+        assert_eq!(problem.unknowns(), 2);
+        assert!(problem.is_valid());
+        assert_eq!(format!("{:?}", problem), "12343412214.43.1");
+        assert_eq!(problem.to_string(), "12 34\n34 12\n\n21 4.\n43 .1");
+    }
+
+    #[test]
+    fn parse_symbols() {
+        let problem = pattern::<4>();
+        assert!(problem.is_valid());
+        assert!(problem.is_complete());
+        assert_eq!(
+            SizedProblem::<4>::from_str(&format!("{:?}", problem).to_lowercase()).unwrap(),
+            problem
+        );
+        assert!(pattern::<5>().is_complete());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            SizedProblem::<2>::from_str("1234"),
+            Err(ParseError::WrongLength {
+                expected: 16,
+                length: 4,
+            })
+        );
+        assert_eq!(
+            SizedProblem::<2>::from_str("1234 5"),
+            Err(ParseError::InvalidSymbol {
+                position: 6,
+                character: '5',
+                side: 4,
+            })
+        );
+        assert_eq!(
+            SizedProblem::<4>::from_str(&"H".repeat(256)),
+            Err(ParseError::InvalidSymbol {
+                position: 1,
+                character: 'H',
+                side: 16,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_duplicates() {
+        assert_eq!(
+            SizedProblem::<2>::from_str("1..1 .... .... ...."),
+            Err(ParseError::DuplicateSymbol {
+                symbol: '1',
+                unit: Unit::Row(1),
+                first: (1, 1),
+                second: (1, 4),
+            })
+        );
+        assert_eq!(
+            SizedProblem::<2>::from_str("1... .... .... 1..."),
+            Err(ParseError::DuplicateSymbol {
+                symbol: '1',
+                unit: Unit::Column(1),
+                first: (1, 1),
+                second: (4, 1),
+            })
+        );
+        assert_eq!(
+            SizedProblem::<4>::from_str(&format!(
+                "{}G{}G{}",
+                ".".repeat(20),
+                ".".repeat(16),
+                ".".repeat(218)
+            ))
+            .unwrap_err()
+            .to_string(),
+            "duplicate G given in square 2 (at r2c5 and r3c6)"
+        );
+    }
+
+    #[test]
+    fn is_valid() {
+        let problem = SizedProblem::<2>::from_str("1... ..1. .... ....").unwrap();
+        assert!(problem.is_valid());
+        let problem = problem.place_digit(5, 0);
+        assert!(!problem.is_valid());
+    }
+
+    #[test]
+    fn place_digit() {
+        let problem = SizedProblem::<2>::default();
+        let placed = problem.place_digit(5, 2);
+        assert_eq!(placed.value(1, 1), 3);
+        assert_eq!(placed.candidate_mask(0), 0b1011);
+        assert_eq!(placed.candidate_mask(15), 0b1111);
+        assert_eq!(placed.peers(5).len(), 7);
+    }
+}
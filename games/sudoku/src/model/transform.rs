@@ -0,0 +1,522 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::unit::CLASSIC;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    // Only the rows, columns & squares are kept by every transformation, so jigsaws, extra regions & constraints are refused.
+    NotClassic,
+    // A row, column, band or stack beyond the grid (they count from 0).
+    OutOfRange { index: usize, limit: usize },
+    // The swapped rows (or columns) must be in the same band (or stack).
+    Apart { first: usize, second: usize },
+    // The relabelling must use each digit once.
+    NotPermutation,
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::NotClassic => write!(
+                f,
+                "only classic problems can be transformed (without a jigsaw, extra regions or constraints)"
+            ),
+            TransformError::OutOfRange { index, limit } => {
+                write!(f, "{} is out of range (below {})", index, limit)
+            }
+            TransformError::Apart { first, second } => write!(
+                f,
+                "{} & {} are not in the same band (or stack)",
+                first, second
+            ),
+            TransformError::NotPermutation => write!(f, "the relabelling must use each digit once"),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+// A change to a classic problem which keeps it valid, and keeps its solutions (transformed the same way).
+// Rows & columns count from 0, as do the bands (of 3 rows) & stacks (of 3 columns).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    // Each digit `d` becomes the value at index `d - 1`.
+    Relabel([SudokuValue; 9]),
+    SwapRows(usize, usize),
+    SwapColumns(usize, usize),
+    SwapBands(usize, usize),
+    SwapStacks(usize, usize),
+    // Reflect in the main diagonal, so the rows become the columns.
+    Transpose,
+    // Turn by the number of quarter turns clockwise.
+    Rotate(usize),
+}
+
+impl Transform {
+    pub fn apply(&self, problem: &SudokuProblem) -> Result<SudokuProblem, TransformError> {
+        check_classic(problem)?;
+        self.validate()?;
+
+        let values = (0..9 * 9)
+            .map(|index| {
+                let (row, column) = self.source(index / 9, index % 9);
+                self.relabel(problem.value(&as_cell(row * 9 + column)))
+            })
+            .collect();
+
+        Ok(problem
+            .with_values(values)
+            .expect("the transformations keep the givens valid"))
+    }
+
+    fn validate(&self) -> Result<(), TransformError> {
+        match *self {
+            Transform::Relabel(values) => {
+                let mask = values
+                    .iter()
+                    .filter_map(|value| value.to_count_index())
+                    .fold(0u16, |mask, digit| mask | (1 << digit));
+
+                if mask == 0b1_1111_1111 {
+                    Ok(())
+                } else {
+                    Err(TransformError::NotPermutation)
+                }
+            }
+            Transform::SwapRows(first, second) | Transform::SwapColumns(first, second) => {
+                in_range(first, 9)?;
+                in_range(second, 9)?;
+
+                if first / 3 == second / 3 {
+                    Ok(())
+                } else {
+                    Err(TransformError::Apart { first, second })
+                }
+            }
+            Transform::SwapBands(first, second) | Transform::SwapStacks(first, second) => {
+                in_range(first, 3)?;
+                in_range(second, 3)
+            }
+            Transform::Transpose | Transform::Rotate(_) => Ok(()),
+        }
+    }
+
+    // The row & column whose value moves to the given row & column.
+    fn source(&self, row: usize, column: usize) -> (usize, usize) {
+        match *self {
+            Transform::Relabel(_) => (row, column),
+            Transform::SwapRows(first, second) => (swap(row, first, second), column),
+            Transform::SwapColumns(first, second) => (row, swap(column, first, second)),
+            Transform::SwapBands(first, second) => {
+                (swap(row / 3, first, second) * 3 + row % 3, column)
+            }
+            Transform::SwapStacks(first, second) => {
+                (row, swap(column / 3, first, second) * 3 + column % 3)
+            }
+            Transform::Transpose => (column, row),
+            Transform::Rotate(turns) => {
+                (0..turns % 4).fold((row, column), |(row, column), _| (8 - column, row))
+            }
+        }
+    }
+
+    fn relabel(&self, value: SudokuValue) -> SudokuValue {
+        match (self, value.to_count_index()) {
+            (Transform::Relabel(values), Some(digit)) => values[digit],
+            _ => value,
+        }
+    }
+}
+
+// The one representative of every problem which the transformations make from this one: the smallest by its digits,
+// read from left to right, top to bottom (with the unknown cells as 0).
+// Equivalent problems share the same canonical form, so it tells apart the duplicates of a collection.
+pub fn canonical(problem: &SudokuProblem) -> Result<SudokuProblem, TransformError> {
+    check_classic(problem)?;
+
+    let digits = (0..9 * 9)
+        .map(|index| digit(problem.value(&as_cell(index))))
+        .collect::<Vec<_>>();
+    let transposed = (0..9 * 9)
+        .map(|index| digits[(index % 9) * 9 + index / 9])
+        .collect::<Vec<_>>();
+    let mut best: Option<[u8; 9 * 9]> = None;
+
+    for grid in [&digits, &transposed].iter() {
+        for columns in column_orders() {
+            if let Some(candidate) = smallest(grid, &columns, best.as_ref()) {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    let values = best
+        .expect("there is always an ordering")
+        .iter()
+        .map(|digit| match digit {
+            0 => SudokuValue::Unknown,
+            _ => SudokuValue::candidates()[*digit as usize - 1],
+        })
+        .collect();
+
+    Ok(problem
+        .with_values(values)
+        .expect("the transformations keep the givens valid"))
+}
+
+// The rows chosen so far (as a bitmask), with the labels given to the digits met in them (from 1, in order; 0 while unmet).
+#[derive(Debug, Clone)]
+struct Ordering {
+    used: u16,
+    labels: [u8; 10],
+    next: u8,
+    band: usize,
+}
+
+// The smallest grid from these columns, over the orders of the rows (within the bands, & of the bands) and the
+// relabellings, or `None` unless it is no bigger than the `bound`.
+// The rows are chosen one at a time, keeping every ordering which ties for the smallest row so far.
+// Once a digit is met it keeps its label, so each new digit takes the next label.
+fn smallest(grid: &[u8], columns: &[usize; 9], bound: Option<&[u8; 9 * 9]>) -> Option<[u8; 9 * 9]> {
+    let mut orderings = vec![Ordering {
+        used: 0,
+        labels: [0; 10],
+        next: 1,
+        band: 0,
+    }];
+    let mut result = [0u8; 9 * 9];
+    let mut tied = bound.is_some();
+
+    for position in 0..9 {
+        let mut best: Option<[u8; 9]> = None;
+        let mut next = Vec::default();
+
+        for ordering in orderings.iter() {
+            // A band is finished before starting the next.
+            let rows = match position % 3 {
+                0 => 0..9,
+                _ => ordering.band * 3..ordering.band * 3 + 3,
+            };
+
+            for row in rows.filter(|row| ordering.used & (1 << row) == 0) {
+                let mut extended = ordering.clone();
+                let mut digits = [0u8; 9];
+
+                for (digit, column) in digits.iter_mut().zip(columns.iter()) {
+                    let value = grid[row * 9 + column] as usize;
+
+                    if value != 0 && extended.labels[value] == 0 {
+                        extended.labels[value] = extended.next;
+                        extended.next += 1;
+                    }
+
+                    *digit = extended.labels[value];
+                }
+
+                extended.used |= 1 << row;
+                extended.band = row / 3;
+
+                match best.map(|best| digits.cmp(&best)) {
+                    Some(std::cmp::Ordering::Greater) => continue,
+                    Some(std::cmp::Ordering::Less) | None => {
+                        best = Some(digits);
+                        next.clear();
+                    }
+                    Some(std::cmp::Ordering::Equal) => {}
+                }
+
+                next.push(extended);
+            }
+        }
+
+        let best = best.expect("there is always an unused row");
+
+        if tied {
+            let bound = &bound.expect("only tied with a bound")[position * 9..position * 9 + 9];
+
+            match best[..].cmp(bound) {
+                std::cmp::Ordering::Greater => return None,
+                std::cmp::Ordering::Less => tied = false,
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+
+        result[position * 9..position * 9 + 9].copy_from_slice(&best);
+
+        // The orderings which used the same rows & labels have the same future, so only one of them is kept.
+        next.sort_by_key(|ordering| (ordering.used, ordering.labels));
+        next.dedup_by(|first, second| (first.used, first.labels) == (second.used, second.labels));
+        orderings = next;
+    }
+
+    Some(result)
+}
+
+// Every order of the columns which keeps the stacks together: the orders of the stacks, & of the columns within each.
+fn column_orders() -> Vec<[usize; 9]> {
+    let orders = permutations();
+    let mut result = Vec::default();
+
+    for stacks in orders.iter() {
+        for first in orders.iter() {
+            for second in orders.iter() {
+                for third in orders.iter() {
+                    let mut columns = [0; 9];
+
+                    for (position, within) in [first, second, third].iter().enumerate() {
+                        for (offset, column) in within.iter().enumerate() {
+                            columns[position * 3 + offset] = stacks[position] * 3 + column;
+                        }
+                    }
+
+                    result.push(columns);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn permutations() -> [[usize; 3]; 6] {
+    [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ]
+}
+
+fn check_classic(problem: &SudokuProblem) -> Result<(), TransformError> {
+    if *problem.units() == **CLASSIC {
+        Ok(())
+    } else {
+        Err(TransformError::NotClassic)
+    }
+}
+
+fn in_range(index: usize, limit: usize) -> Result<(), TransformError> {
+    if index < limit {
+        Ok(())
+    } else {
+        Err(TransformError::OutOfRange { index, limit })
+    }
+}
+
+fn swap(index: usize, first: usize, second: usize) -> usize {
+    match index {
+        _ if index == first => second,
+        _ if index == second => first,
+        _ => index,
+    }
+}
+
+fn as_cell(index: usize) -> Cell {
+    Cell::index(index).expect("must be a valid index")
+}
+
+fn digit(value: SudokuValue) -> u8 {
+    value.to_count_index().map_or(0, |digit| digit as u8 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::constraint::Constraint;
+    use crate::model::variant::Variant;
+    use std::str::FromStr;
+
+    const SDM: &str =
+        "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+    const SOLUTION: &str =
+        "379526814564318972281479365435267198698143527712895436923754681146982753857631249";
+
+    fn problem(sdm: &str) -> SudokuProblem {
+        SudokuProblem::from_str(sdm).unwrap()
+    }
+
+    fn row(problem: &SudokuProblem, row: usize) -> String {
+        format!("{:?}", problem)[row * 9..row * 9 + 9].to_string()
+    }
+
+    fn column(problem: &SudokuProblem, column: usize) -> String {
+        format!("{:?}", problem)
+            .chars()
+            .skip(column)
+            .step_by(9)
+            .collect()
+    }
+
+    #[test]
+    fn relabel() {
+        // Setup
+        let mut values = [SudokuValue::Unknown; 9];
+        values.copy_from_slice(SudokuValue::candidates());
+        values.swap(0, 8);
+
+        // Execute
+        let relabelled = Transform::Relabel(values).apply(&problem(SDM)).unwrap();
+
+        // Verify
+        assert_eq!(row(&relabelled, 0), "371000094");
+    }
+
+    #[test]
+    fn swaps() {
+        let sdm = problem(SDM);
+
+        let swapped = Transform::SwapRows(0, 2).apply(&sdm).unwrap();
+        assert_eq!(row(&swapped, 0), row(&sdm, 2));
+        assert_eq!(row(&swapped, 2), row(&sdm, 0));
+        assert_eq!(row(&swapped, 1), row(&sdm, 1));
+
+        let swapped = Transform::SwapColumns(4, 5).apply(&sdm).unwrap();
+        assert_eq!(column(&swapped, 4), column(&sdm, 5));
+        assert_eq!(column(&swapped, 5), column(&sdm, 4));
+
+        let swapped = Transform::SwapBands(0, 2).apply(&sdm).unwrap();
+        assert_eq!(row(&swapped, 1), row(&sdm, 7));
+        assert_eq!(row(&swapped, 7), row(&sdm, 1));
+        assert_eq!(row(&swapped, 4), row(&sdm, 4));
+
+        let swapped = Transform::SwapStacks(1, 2).apply(&sdm).unwrap();
+        assert_eq!(column(&swapped, 3), column(&sdm, 6));
+        assert_eq!(column(&swapped, 8), column(&sdm, 5));
+    }
+
+    #[test]
+    fn transpose_rotate() {
+        // Setup
+        let sdm = problem(SDM);
+
+        // Execute
+        let transposed = Transform::Transpose.apply(&sdm).unwrap();
+        let turned = Transform::Rotate(1).apply(&sdm).unwrap();
+        let half = Transform::Rotate(2).apply(&sdm).unwrap();
+
+        // Verify
+        assert_eq!(row(&transposed, 0), column(&sdm, 0));
+        // A quarter turn clockwise takes the bottom row to the left column.
+        assert_eq!(
+            row(&turned, 0),
+            column(&sdm, 0).chars().rev().collect::<String>()
+        );
+        assert_eq!(
+            format!("{:?}", half),
+            format!("{:?}", sdm).chars().rev().collect::<String>()
+        );
+        assert_eq!(Transform::Rotate(4).apply(&sdm).unwrap(), sdm);
+        assert_eq!(
+            Transform::Rotate(1).apply(&half).unwrap(),
+            Transform::Rotate(3).apply(&sdm).unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_solutions() {
+        // Setup
+        let transforms = [
+            Transform::SwapRows(3, 5),
+            Transform::SwapColumns(7, 6),
+            Transform::SwapBands(1, 0),
+            Transform::SwapStacks(0, 2),
+            Transform::Transpose,
+            Transform::Rotate(3),
+        ];
+
+        for transform in transforms.iter() {
+            // Execute
+            let puzzle = transform.apply(&problem(SDM)).unwrap();
+            let solution = transform.apply(&problem(SOLUTION)).unwrap();
+
+            // Verify
+            assert!(solution.is_valid(), "{:?}", transform);
+            assert!(solution.is_complete());
+            for index in 0..9 * 9 {
+                let cell = as_cell(index);
+                let value = puzzle.value(&cell);
+                assert!(value == SudokuValue::Unknown || value == solution.value(&cell));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let sdm = problem(SDM);
+        assert_eq!(
+            Transform::SwapRows(2, 3).apply(&sdm),
+            Err(TransformError::Apart {
+                first: 2,
+                second: 3
+            })
+        );
+        assert_eq!(
+            Transform::SwapStacks(0, 3).apply(&sdm),
+            Err(TransformError::OutOfRange { index: 3, limit: 3 })
+        );
+        assert_eq!(
+            Transform::Relabel([SudokuValue::One; 9]).apply(&sdm),
+            Err(TransformError::NotPermutation)
+        );
+
+        let x = SudokuProblem::default().with_variant(Variant::X).unwrap();
+        assert_eq!(
+            Transform::Transpose.apply(&x),
+            Err(TransformError::NotClassic)
+        );
+        let miracle = SudokuProblem::default()
+            .with_constraint(Constraint::AntiKnight)
+            .unwrap();
+        assert_eq!(canonical(&miracle), Err(TransformError::NotClassic));
+    }
+
+    #[test]
+    fn canonical_form() {
+        // Setup
+        let sdm = problem(SDM);
+        let mut values = [SudokuValue::Unknown; 9];
+        values.copy_from_slice(SudokuValue::candidates());
+        values.rotate_left(4);
+        let equivalent = [
+            Transform::Relabel(values),
+            Transform::SwapRows(6, 8),
+            Transform::SwapColumns(1, 2),
+            Transform::SwapBands(0, 1),
+            Transform::SwapStacks(2, 1),
+            Transform::Transpose,
+            Transform::Rotate(1),
+        ]
+        .iter()
+        .try_fold(sdm.clone(), |problem, transform| transform.apply(&problem))
+        .unwrap();
+
+        // Execute
+        let form = canonical(&sdm).unwrap();
+
+        // Verify
+        assert_eq!(canonical(&equivalent).unwrap(), form);
+        assert_eq!(canonical(&form).unwrap(), form);
+        assert_eq!(form.unknowns(), sdm.unknowns());
+        assert!(form.is_valid());
+        // Nothing equivalent is smaller, as the unknown cells (0) come first.
+        for transform in [
+            Transform::Transpose,
+            Transform::Rotate(1),
+            Transform::SwapRows(0, 1),
+        ]
+        .iter()
+        {
+            let other = transform.apply(&form).unwrap();
+            assert!(format!("{:?}", form) <= format!("{:?}", other));
+        }
+        assert_ne!(canonical(&problem(SOLUTION)).unwrap(), form);
+    }
+
+    #[test]
+    fn canonical_empty() {
+        let empty = SudokuProblem::default();
+        assert_eq!(canonical(&empty).unwrap(), empty);
+    }
+}
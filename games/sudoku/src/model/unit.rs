@@ -0,0 +1,339 @@
+use crate::model::board::Unit;
+use crate::model::cell::Cell;
+use crate::model::constraint::Constraint;
+use lazy_static::lazy_static;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+// The largest box size supported, for 25x25 grids; every digit must fit in a `u32` bitmask.
+pub(crate) const MAX_BOX_SIZE: usize = 5;
+
+lazy_static! {
+    // The 27 units of the grid, by cell index: rows (0..9), then columns (9..18), then squares (18..27).
+    pub(crate) static ref UNITS: Vec<[usize; 9]> = build_units();
+    // The 3 units (row, column, square) which each cell belongs to.
+    pub(crate) static ref CELL_UNITS: Vec<[usize; 3]> = layout(3).cell_units.clone();
+    static ref LAYOUTS: Vec<Layout> = (1..=MAX_BOX_SIZE).map(Layout::new).collect();
+    // The units of a problem without any extra regions, shared by every such problem.
+    // Each cell has 20 peers: the other cells which share a unit with it.
+    pub(crate) static ref CLASSIC: Arc<Units> = Arc::new(Units::new(&UNITS[18..27], Vec::default()));
+}
+
+// The units of a 9x9 problem: the rows & columns of `UNITS`, then the 9 boxes (the squares, or the jigsaw regions), then any extra regions.
+// Every unit holds 9 cells, so must hold each digit exactly once.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Units {
+    pub(crate) units: Vec<[usize; 9]>,
+    // The units which each cell belongs to, starting with its row, column & box (as in `CELL_UNITS`).
+    pub(crate) cell_units: Vec<Vec<usize>>,
+    // The cells which may not hold the same digit as each cell: those which share a unit, or else an anti-knight (or anti-king) move.
+    pub(crate) peers: Vec<Vec<usize>>,
+    pub(crate) constraints: Vec<Constraint>,
+    // The cells which may not hold a digit consecutive to that of each cell (under the non-consecutive constraint).
+    pub(crate) neighbours: Vec<Vec<usize>>,
+}
+
+impl Units {
+    // The `boxes` must partition the grid, so that each cell is in exactly one of them.
+    pub(crate) fn new(boxes: &[[usize; 9]], regions: Vec<[usize; 9]>) -> Units {
+        debug_assert!(boxes.len() == 9);
+        let units = UNITS[0..18]
+            .iter()
+            .chain(boxes.iter())
+            .copied()
+            .chain(regions)
+            .collect::<Vec<_>>();
+        let cell_units = (0..9 * 9)
+            .map(|index| {
+                (0..units.len())
+                    .filter(|unit| units[*unit].contains(&index))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let peers = cell_units
+            .iter()
+            .enumerate()
+            .map(|(index, cell_units)| {
+                let mut peers: Vec<usize> = cell_units
+                    .iter()
+                    .flat_map(|unit| units[*unit].iter().copied())
+                    .filter(|peer| *peer != index)
+                    .collect();
+                peers.sort_unstable();
+                peers.dedup();
+                peers
+            })
+            .collect();
+
+        Self {
+            units,
+            cell_units,
+            peers,
+            constraints: Vec::default(),
+            neighbours: vec![Vec::default(); 9 * 9],
+        }
+    }
+
+    // Relate the cells a move apart under each of the constraints, as peers or as neighbours.
+    pub(crate) fn with_constraints(mut self, constraints: Vec<Constraint>) -> Units {
+        for constraint in constraints.iter() {
+            for index in 0..9 * 9 {
+                let cell = Cell::index(index).expect("must be a valid index");
+                let related = if constraint.is_distinct() {
+                    &mut self.peers[index]
+                } else {
+                    &mut self.neighbours[index]
+                };
+
+                for other in constraint.cells(&cell) {
+                    if !related.contains(&other.index) {
+                        related.push(other.index);
+                    }
+                }
+
+                related.sort_unstable();
+            }
+        }
+
+        self.constraints = constraints;
+        self
+    }
+
+    pub(crate) fn share_unit(&self, index: usize, other: usize) -> bool {
+        self.shared_unit(index, other).is_some()
+    }
+
+    // The first unit which holds both cells.
+    pub(crate) fn shared_unit(&self, index: usize, other: usize) -> Option<Unit> {
+        self.cell_units[index]
+            .iter()
+            .find(|unit| self.cell_units[other].contains(unit))
+            .map(|unit| self.unit(*unit))
+    }
+
+    // Describe the unit, by its kind & number.
+    pub(crate) fn unit(&self, unit: usize) -> Unit {
+        match unit {
+            0..=8 => Unit::Row(unit + 1),
+            9..=17 => Unit::Column(unit - 8),
+            18..=26 if self.boxes() == &UNITS[18..27] => Unit::Square(unit - 17),
+            18..=26 => Unit::Jigsaw(unit - 17),
+            _ => Unit::Region(unit - 26),
+        }
+    }
+
+    pub(crate) fn boxes(&self) -> &[[usize; 9]] {
+        &self.units[18..27]
+    }
+
+    // The units beyond the rows, columns & boxes.
+    pub(crate) fn regions(&self) -> &[[usize; 9]] {
+        &self.units[27..]
+    }
+}
+
+// The units of a grid with boxes of `box_size` x `box_size` cells, by cell index.
+// As with `UNITS`, the rows come first, then the columns, then the boxes.
+#[derive(Debug)]
+pub(crate) struct Layout {
+    pub(crate) units: Vec<Vec<usize>>,
+    pub(crate) cell_units: Vec<[usize; 3]>,
+    pub(crate) peers: Vec<Vec<usize>>,
+}
+
+impl Layout {
+    fn new(box_size: usize) -> Layout {
+        let side = box_size * box_size;
+        let mut units: Vec<Vec<usize>> = Vec::with_capacity(3 * side);
+
+        for i in 0..side {
+            units.push((0..side).map(|j| (i * side) + j).collect());
+        }
+
+        for j in 0..side {
+            units.push((0..side).map(|i| (i * side) + j).collect());
+        }
+
+        for x in 0..side {
+            let initial = ((x / box_size) * side * box_size) + ((x % box_size) * box_size);
+            units.push(
+                (0..side)
+                    .map(|y| initial + ((y / box_size) * side) + (y % box_size))
+                    .collect(),
+            );
+        }
+
+        let cell_units = (0..side * side)
+            .map(|index| {
+                let row = index / side;
+                let column = index % side;
+                let square = ((row / box_size) * box_size) + (column / box_size);
+                [row, side + column, (2 * side) + square]
+            })
+            .collect::<Vec<_>>();
+        let peers = cell_units
+            .iter()
+            .enumerate()
+            .map(|(index, cell_units)| {
+                let mut peers: Vec<usize> = cell_units
+                    .iter()
+                    .flat_map(|unit| units[*unit].iter().copied())
+                    .filter(|peer| *peer != index)
+                    .collect();
+                peers.sort_unstable();
+                peers.dedup();
+                peers
+            })
+            .collect();
+
+        Self {
+            units,
+            cell_units,
+            peers,
+        }
+    }
+}
+
+pub(crate) fn layout(box_size: usize) -> &'static Layout {
+    &LAYOUTS[box_size - 1]
+}
+
+fn build_units() -> Vec<[usize; 9]> {
+    layout(3)
+        .units
+        .iter()
+        .map(|unit| {
+            unit.as_slice()
+                .try_into()
+                .expect("every unit must have exactly 9 cells")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units() {
+        assert_eq!(UNITS.len(), 27);
+        assert_eq!(UNITS[0], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(UNITS[9], [0, 9, 18, 27, 36, 45, 54, 63, 72]);
+        assert_eq!(UNITS[18], [0, 1, 2, 9, 10, 11, 18, 19, 20]);
+        assert_eq!(UNITS[26], [60, 61, 62, 69, 70, 71, 78, 79, 80]);
+    }
+
+    #[test]
+    fn cell_units() {
+        assert_eq!(CELL_UNITS[0], [0, 9, 18]);
+        assert_eq!(CELL_UNITS[40], [4, 13, 22]);
+        assert_eq!(CELL_UNITS[80], [8, 17, 26]);
+
+        for (index, cell_units) in CELL_UNITS.iter().enumerate() {
+            for unit in cell_units {
+                assert!(UNITS[*unit].contains(&index));
+            }
+        }
+    }
+
+    #[test]
+    fn peers() {
+        assert_eq!(
+            CLASSIC.peers[0],
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 18, 19, 20, 27, 36, 45, 54, 63, 72]
+        );
+
+        for (index, peers) in CLASSIC.peers.iter().enumerate() {
+            assert!(!peers.contains(&index));
+        }
+    }
+
+    #[test]
+    fn layouts() {
+        for box_size in 1..=MAX_BOX_SIZE {
+            let side = box_size * box_size;
+            let layout = layout(box_size);
+            assert_eq!(layout.units.len(), 3 * side);
+            assert_eq!(layout.cell_units.len(), side * side);
+
+            for (index, peers) in layout.peers.iter().enumerate() {
+                // The row & column, plus the rest of the box.
+                assert_eq!(
+                    peers.len(),
+                    (2 * (side - 1)) + ((side + 1) - (2 * box_size))
+                );
+                assert!(!peers.contains(&index));
+            }
+        }
+
+        assert_eq!(layout(2).units[8], [0, 1, 4, 5]);
+        assert_eq!(layout(2).units[11], [10, 11, 14, 15]);
+    }
+
+    #[test]
+    fn extra_units() {
+        // Setup
+        let diagonal = [0, 10, 20, 30, 40, 50, 60, 70, 80];
+
+        // Execute
+        let units = Units::new(CLASSIC.boxes(), vec![diagonal]);
+
+        // Verify
+        assert_eq!(units.units.len(), 28);
+        assert_eq!(units.regions(), [diagonal]);
+        assert_eq!(units.cell_units[0], [0, 9, 18, 27]);
+        assert_eq!(units.cell_units[1], [0, 10, 18]);
+        assert_eq!(units.peers[0].len(), 20 + 6);
+        assert!(units.peers[0].contains(&80));
+        assert_eq!(units.peers[1], CLASSIC.peers[1]);
+        assert_eq!(CLASSIC.cell_units[40], CELL_UNITS[40]);
+    }
+
+    #[test]
+    fn constraints() {
+        // Setup
+        let constraints = vec![
+            Constraint::AntiKnight,
+            Constraint::AntiKing,
+            Constraint::NonConsecutive,
+        ];
+
+        // Execute
+        let units = Units::new(CLASSIC.boxes(), Vec::default()).with_constraints(constraints);
+
+        // Verify
+        // r3c3 gains 6 knight moves & 3 king moves (such as r2c4) outside of its row, column & square.
+        assert_eq!(units.peers[20].len(), 20 + 6 + 3);
+        assert!(units.peers[20].contains(&12));
+        assert!(!units.share_unit(20, 12));
+        assert!(units.share_unit(0, 20));
+        assert_eq!(units.shared_unit(20, 12), None);
+        assert_eq!(units.shared_unit(0, 20), Some(Unit::Square(1)));
+        assert_eq!(units.shared_unit(0, 8), Some(Unit::Row(1)));
+        assert_eq!(units.shared_unit(8, 80), Some(Unit::Column(9)));
+        // Whereas r5c5 only gains its 8 knight moves.
+        assert_eq!(units.peers[40].len(), 20 + 8);
+        assert_eq!(units.neighbours[40], [31, 39, 41, 49]);
+        assert_eq!(CLASSIC.neighbours[40], []);
+    }
+
+    #[test]
+    fn custom_boxes() {
+        // Setup
+        // Each row of the grid as a box, with the first two cells of the top two rows swapped over.
+        let mut boxes = UNITS[0..9].to_vec();
+        boxes[0][0] = 9;
+        boxes[1][0] = 0;
+
+        // Execute
+        let units = Units::new(&boxes, Vec::default());
+
+        // Verify
+        assert_eq!(units.boxes(), boxes.as_slice());
+        assert_eq!(units.cell_units[0], [0, 9, 19]);
+        assert_eq!(units.cell_units[9], [1, 9, 18]);
+        assert_eq!(units.peers[1].len(), 8 + 8 + 1);
+        assert!(units.peers[0].contains(&10));
+        assert!(!units.peers[0].contains(&20));
+    }
+}
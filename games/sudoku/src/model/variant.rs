@@ -0,0 +1,95 @@
+use crate::model::cell::Cell;
+
+const VARIANTS: [Variant; 3] = [Variant::Classic, Variant::X, Variant::Hyper];
+
+// The extra regions of a problem, each of which must hold the digits 1-9 exactly once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    #[default]
+    Classic,
+    // Sudoku-X: the two main diagonals.
+    X,
+    // Hyper (or Windoku): the four 3x3 windows between the squares, with their top left cells at r2c2, r2c6, r6c2 & r6c6.
+    Hyper,
+}
+
+impl Variant {
+    pub fn all() -> &'static [Variant] {
+        &VARIANTS[..]
+    }
+
+    pub fn regions(&self) -> Vec<[Cell; 9]> {
+        let cell = |row: usize, column: usize| {
+            Cell::row_column(row, column).expect("must be a valid cell")
+        };
+
+        match self {
+            Variant::Classic => Vec::default(),
+            Variant::X => vec![
+                [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|i| cell(i, i)),
+                [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|i| cell(i, 8 - i)),
+            ],
+            Variant::Hyper => [(1, 1), (1, 5), (5, 1), (5, 5)]
+                .iter()
+                .map(|(top, left)| {
+                    [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|i| cell(top + (i / 3), left + (i % 3)))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for Variant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "classic" => Ok(Variant::Classic),
+            "x" | "sudoku-x" => Ok(Variant::X),
+            "hyper" | "windoku" => Ok(Variant::Hyper),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Variant::Classic => write!(f, "classic"),
+            Variant::X => write!(f, "x"),
+            Variant::Hyper => write!(f, "hyper"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn variant_round_trip() {
+        for variant in Variant::all() {
+            assert_eq!(Variant::from_str(&variant.to_string()).unwrap(), *variant);
+        }
+
+        assert_eq!(Variant::from_str("windoku").unwrap(), Variant::Hyper);
+        Variant::from_str("jigsaw").unwrap_err();
+    }
+
+    #[test]
+    fn regions() {
+        assert!(Variant::Classic.regions().is_empty());
+
+        let diagonals = Variant::X.regions();
+        assert_eq!(diagonals.len(), 2);
+        assert_eq!(diagonals[0][4], Cell::row_column(4, 4).unwrap());
+        assert_eq!(diagonals[1][0], Cell::row_column(0, 8).unwrap());
+        assert_eq!(diagonals[1][8], Cell::row_column(8, 0).unwrap());
+
+        let windows = Variant::Hyper.regions();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[0][0], Cell::row_column(1, 1).unwrap());
+        assert_eq!(windows[3][8], Cell::row_column(7, 7).unwrap());
+    }
+}
@@ -0,0 +1,806 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::pencil::PencilMarks;
+use crate::model::render::Renderer;
+use crate::solver::logical::{Logical, Technique};
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+const USAGE: &str =
+    "moves: r3c5=7 to place a digit, r3c5=0 to clear it, r3c5+1,2 to pencil in & r3c5-1,2 to rub out candidates; undo, redo or quit";
+const ASSISTANCE: &str =
+    "assistance: hint (ask again for more detail) or check to find the wrong digits";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayError {
+    InvalidInput { input: String },
+    // The givens of the puzzle cannot be changed.
+    Given { cell: Cell },
+    // Only the unknown cells take pencil marks.
+    Filled { cell: Cell },
+    NothingToUndo,
+    NothingToRedo,
+    // Hints & checks need the solution, which only an assisted player is given.
+    Unassisted,
+    // There is nothing left to hint at.
+    Solved,
+}
+
+impl std::fmt::Display for PlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayError::InvalidInput { input } => write!(f, "invalid input '{}' ({})", input, USAGE),
+            PlayError::Given { cell } => write!(f, "{} is a given", cell),
+            PlayError::Filled { cell } => write!(f, "{} already holds a digit", cell),
+            PlayError::NothingToUndo => write!(f, "nothing to undo"),
+            PlayError::NothingToRedo => write!(f, "nothing to redo"),
+            PlayError::Unassisted => write!(f, "hints & checks are for assisted players"),
+            PlayError::Solved => write!(f, "the puzzle is already solved"),
+        }
+    }
+}
+
+impl std::error::Error for PlayError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Move {
+    // Place the value into the cell, or clear it with `Unknown`.
+    Place(Cell, SudokuValue),
+    Mark(Cell, Vec<SudokuValue>),
+    Unmark(Cell, Vec<SudokuValue>),
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = |values: &[SudokuValue]| {
+            values
+                .iter()
+                .map(|value| value.to_char().to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        match self {
+            Move::Place(cell, value) => write!(f, "{}={}", cell, value.to_char()),
+            Move::Mark(cell, values_) => write!(f, "{}+{}", cell, values(values_)),
+            Move::Unmark(cell, values_) => write!(f, "{}-{}", cell, values(values_)),
+        }
+    }
+}
+
+// What the player types in, one per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Move(Move),
+    Undo,
+    Redo,
+    Hint,
+    Check,
+    Quit,
+}
+
+// `undo`, `redo`, `hint`, `check`, `quit`, or a move: `r3c5=7` places a 7 (`=0` clears the cell), `r3c5+1,2` pencils in a 1 & 2 and
+// `r3c5-1,2` rubs them out again.  Case & spaces are ignored.
+impl FromStr for Action {
+    type Err = PlayError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || PlayError::InvalidInput {
+            input: input.trim().to_string(),
+        };
+        let text = input
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+
+        match text.as_str() {
+            "undo" | "u" => return Ok(Action::Undo),
+            "redo" | "r" => return Ok(Action::Redo),
+            "hint" | "h" => return Ok(Action::Hint),
+            "check" | "c" => return Ok(Action::Check),
+            "quit" | "q" => return Ok(Action::Quit),
+            _ => {}
+        }
+
+        let split = text.find(['=', '+', '-']).ok_or_else(invalid)?;
+        let cell = Cell::from_str(&text[..split]).map_err(|_| invalid())?;
+        let values = text[split + 1..]
+            .split(',')
+            .map(|value| match value.chars().collect::<Vec<_>>().as_slice() {
+                [character] => SudokuValue::try_from(*character).map_err(|_| invalid()),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match (&text[split..split + 1], values.as_slice()) {
+            ("=", [value]) => Ok(Action::Move(Move::Place(cell, *value))),
+            ("+" | "-", _) if values.contains(&SudokuValue::Unknown) => Err(invalid()),
+            ("+", _) => Ok(Action::Move(Move::Mark(cell, values))),
+            ("-", _) => Ok(Action::Move(Move::Unmark(cell, values))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// Each hint in a row reveals more: first the region, then the techniques & finally the placement they lead to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    // The box or jigsaw region, from 1.
+    Region(usize),
+    // The techniques which lead to the placement, from the first applied; none when they all get stuck.
+    Techniques(Vec<Technique>),
+    Placement(Cell, SudokuValue),
+}
+
+impl std::fmt::Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hint::Region(region) => write!(f, "look at box {}", region),
+            Hint::Techniques(techniques) if techniques.is_empty() => {
+                write!(
+                    f,
+                    "none of the techniques apply, so it will take trial & error"
+                )
+            }
+            Hint::Techniques(techniques) => {
+                let techniques = techniques
+                    .iter()
+                    .map(Technique::to_string)
+                    .collect::<Vec<_>>();
+                write!(f, "try a {}", techniques.join(", then a "))
+            }
+            Hint::Placement(cell, value) => write!(f, "{} is a {}", cell, value.to_char()),
+        }
+    }
+}
+
+// A puzzle being played: the digits & pencil marks filled in so far, with the history of moves to undo & redo.
+#[derive(Debug, Clone)]
+pub struct Game {
+    givens: SudokuProblem,
+    board: SudokuProblem,
+    pencil_marks: PencilMarks,
+    // The boards & pencil marks before each move, and after each undone move.
+    undo: Vec<(SudokuProblem, PencilMarks)>,
+    redo: Vec<(SudokuProblem, PencilMarks)>,
+    // The unique solution, for an assisted player.
+    solution: Option<SudokuProblem>,
+    // How many hints have been given since the last change to the board.
+    hints: usize,
+    colour: bool,
+}
+
+impl Game {
+    pub fn new(givens: SudokuProblem) -> Game {
+        Self {
+            board: givens.clone(),
+            givens,
+            pencil_marks: PencilMarks::default(),
+            undo: Vec::default(),
+            redo: Vec::default(),
+            solution: None,
+            hints: 0,
+            colour: false,
+        }
+    }
+
+    // Assist the player with hints & checks, against the puzzle's unique solution.
+    pub fn with_solution(mut self, solution: SudokuProblem) -> Game {
+        self.solution = Some(solution);
+        self
+    }
+
+    pub fn with_colour(mut self, colour: bool) -> Game {
+        self.colour = colour;
+        self
+    }
+
+    pub fn board(&self) -> &SudokuProblem {
+        &self.board
+    }
+
+    pub fn pencil_marks(&self) -> &PencilMarks {
+        &self.pencil_marks
+    }
+
+    // Complete, without breaking any rule.
+    pub fn is_solved(&self) -> bool {
+        self.board.is_complete() && self.board.is_valid()
+    }
+
+    pub fn play(&mut self, player_move: &Move) -> Result<(), PlayError> {
+        let (cell, filling) = match player_move {
+            Move::Place(cell, _) => (cell, false),
+            Move::Mark(cell, _) | Move::Unmark(cell, _) => (cell, true),
+        };
+
+        if self.givens.value(cell) != SudokuValue::Unknown {
+            return Err(PlayError::Given { cell: *cell });
+        }
+
+        if filling && self.board.value(cell) != SudokuValue::Unknown {
+            return Err(PlayError::Filled { cell: *cell });
+        }
+
+        self.undo
+            .push((self.board.clone(), self.pencil_marks.clone()));
+        self.redo.clear();
+        self.hints = 0;
+
+        match player_move {
+            Move::Place(cell, value) => {
+                self.board = self.board.replace(cell, *value);
+                self.pencil_marks.clear(cell);
+            }
+            Move::Mark(cell, values) => {
+                for value in values {
+                    self.pencil_marks.insert(cell, *value);
+                }
+            }
+            Move::Unmark(cell, values) => {
+                for value in values {
+                    self.pencil_marks.remove(cell, *value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Result<(), PlayError> {
+        let (board, pencil_marks) = self.undo.pop().ok_or(PlayError::NothingToUndo)?;
+        self.hints = 0;
+        self.redo.push((
+            std::mem::replace(&mut self.board, board),
+            std::mem::replace(&mut self.pencil_marks, pencil_marks),
+        ));
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), PlayError> {
+        let (board, pencil_marks) = self.redo.pop().ok_or(PlayError::NothingToRedo)?;
+        self.hints = 0;
+        self.undo.push((
+            std::mem::replace(&mut self.board, board),
+            std::mem::replace(&mut self.pencil_marks, pencil_marks),
+        ));
+        Ok(())
+    }
+
+    // The next hint towards a placement, revealing a little more each time it is asked for.
+    // The hints start from the correct digits only, so that the wrong ones cannot lead the techniques astray.
+    pub fn hint(&mut self) -> Result<Hint, PlayError> {
+        let solution = self.solution.as_ref().ok_or(PlayError::Unassisted)?;
+        let correct = (0..9 * 9)
+            .map(|index| {
+                let cell = Cell::index(index).expect("must be a valid index");
+                match self.board.value(&cell) {
+                    value if value == solution.value(&cell) => value,
+                    _ => SudokuValue::Unknown,
+                }
+            })
+            .collect();
+        let correct = self
+            .givens
+            .with_values(correct)
+            .expect("the correct digits are from the solution");
+
+        let mut logical = Logical::new(correct);
+        let mut techniques: Vec<Technique> = Vec::default();
+        let placement = logical.by_ref().find_map(|step| {
+            if !techniques.contains(&step.technique) {
+                techniques.push(step.technique);
+            }
+            step.placement
+        });
+
+        // When the techniques get stuck, point to the cell with the fewest candidates left instead.
+        let (cell, value) = match placement {
+            Some(placement) => placement,
+            None => {
+                techniques.clear();
+                let index = (0..9 * 9)
+                    .filter(|index| logical.pencil_marks().mask(*index) != 0)
+                    .min_by_key(|index| logical.pencil_marks().mask(*index).count_ones())
+                    .ok_or(PlayError::Solved)?;
+                let cell = Cell::index(index).expect("must be a valid index");
+                (cell, solution.value(&cell))
+            }
+        };
+
+        let hint = match self.hints {
+            0 => Hint::Region(region(&self.givens, &cell)),
+            1 => Hint::Techniques(techniques),
+            _ => Hint::Placement(cell, value),
+        };
+        self.hints += 1;
+        Ok(hint)
+    }
+
+    // The digits which differ from the solution, without giving away which of the others are right.
+    pub fn check(&self) -> Result<Vec<Cell>, PlayError> {
+        let solution = self.solution.as_ref().ok_or(PlayError::Unassisted)?;
+        Ok((0..9 * 9)
+            .map(|index| Cell::index(index).expect("must be a valid index"))
+            .filter(|cell| {
+                let value = self.board.value(cell);
+                value != SudokuValue::Unknown && value != solution.value(cell)
+            })
+            .collect())
+    }
+
+    // The board, with the conflicts highlighted, and every cell's pencil marks once there are any.
+    pub fn render(&self) -> String {
+        let conflicts = self.board.conflicts();
+        let renderer = Renderer::new()
+            .with_givens(&self.givens)
+            .with_conflicts(&conflicts)
+            .with_colour(self.colour);
+
+        if self.pencil_marks.is_empty() {
+            renderer.render(&self.board)
+        } else {
+            renderer
+                .with_pencil_marks(&self.pencil_marks)
+                .render(&self.board)
+        }
+    }
+
+    // The board, then whether it is solved or else which cells conflict.
+    fn show(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(writer, "{}", self.render())?;
+
+        if self.is_solved() {
+            writeln!(writer, "solved!")?;
+        } else if !self.board.is_valid() {
+            let conflicts = self
+                .board
+                .conflicts()
+                .iter()
+                .map(Cell::to_string)
+                .collect::<Vec<_>>();
+            writeln!(writer, "conflicts: {}", conflicts.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    // Play the actions read from the `reader` (one per line), showing the board after each of them.
+    // Stops once the puzzle is solved, or when the player quits or the input runs out; returns whether it was solved.
+    pub fn run(&mut self, reader: impl BufRead, writer: &mut impl Write) -> std::io::Result<bool> {
+        writeln!(writer, "{}", USAGE)?;
+        if self.solution.is_some() {
+            writeln!(writer, "{}", ASSISTANCE)?;
+        }
+        self.show(writer)?;
+        let mut lines = reader.lines();
+
+        while !self.is_solved() {
+            write!(writer, "> ")?;
+            writer.flush()?;
+
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => break,
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let outcome = match Action::from_str(&line) {
+                Ok(Action::Quit) => break,
+                Ok(Action::Hint) => {
+                    match self.hint() {
+                        Ok(hint) => writeln!(writer, "{}", hint)?,
+                        Err(error) => writeln!(writer, "{}", error)?,
+                    }
+                    continue;
+                }
+                Ok(Action::Check) => {
+                    match self.check() {
+                        Ok(wrong) if wrong.is_empty() => writeln!(writer, "no wrong digits")?,
+                        Ok(wrong) => {
+                            let wrong = wrong.iter().map(Cell::to_string).collect::<Vec<_>>();
+                            writeln!(writer, "wrong: {}", wrong.join(" "))?
+                        }
+                        Err(error) => writeln!(writer, "{}", error)?,
+                    }
+                    continue;
+                }
+                Ok(Action::Undo) => self.undo(),
+                Ok(Action::Redo) => self.redo(),
+                Ok(Action::Move(player_move)) => self.play(&player_move),
+                Err(error) => Err(error),
+            };
+
+            match outcome {
+                Ok(()) => self.show(writer)?,
+                Err(error) => writeln!(writer, "{}", error)?,
+            }
+        }
+
+        Ok(self.is_solved())
+    }
+
+    // Fill in the unknown cells from the solution, one move at a time, writing each move and then the board.
+    pub fn autoplay(
+        &mut self,
+        solution: &SudokuProblem,
+        writer: &mut impl Write,
+    ) -> std::io::Result<bool> {
+        for index in 0..9 * 9 {
+            let cell = Cell::index(index).expect("must be a valid index");
+
+            if self.board.value(&cell) == SudokuValue::Unknown {
+                let player_move = Move::Place(cell, solution.value(&cell));
+                self.play(&player_move)
+                    .expect("the unknown cells are not givens");
+                writeln!(writer, "{}", player_move)?;
+            }
+        }
+
+        self.show(writer)?;
+        Ok(self.is_solved())
+    }
+}
+
+// The box (or jigsaw region) of the cell, numbered from 1.
+fn region(problem: &SudokuProblem, cell: &Cell) -> usize {
+    let index = cell.row() * 9 + cell.column();
+    problem
+        .units()
+        .boxes()
+        .iter()
+        .position(|region| region.contains(&index))
+        .expect("every cell is in a region")
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const SDM: &str =
+        "379000014060010070080009005435007000090040020000800436900700080040080050850000249";
+    const SOLUTION: &str =
+        "379526814564318972281479365435267198698143527712895436923754681146982753857631249";
+
+    fn cell(text: &str) -> Cell {
+        Cell::from_str(text).unwrap()
+    }
+
+    fn game(sdm: &str) -> Game {
+        Game::new(SudokuProblem::from_str(sdm).unwrap())
+    }
+
+    #[test]
+    fn parse_action() {
+        assert_eq!(
+            Action::from_str("r3c5=7"),
+            Ok(Action::Move(Move::Place(cell("r3c5"), SudokuValue::Seven)))
+        );
+        assert_eq!(
+            Action::from_str(" R3C5 = 0 "),
+            Ok(Action::Move(Move::Place(
+                cell("r3c5"),
+                SudokuValue::Unknown
+            )))
+        );
+        assert_eq!(
+            Action::from_str("r3c5+1,2"),
+            Ok(Action::Move(Move::Mark(
+                cell("r3c5"),
+                vec![SudokuValue::One, SudokuValue::Two]
+            )))
+        );
+        assert_eq!(
+            Action::from_str("r3c5-9"),
+            Ok(Action::Move(Move::Unmark(
+                cell("r3c5"),
+                vec![SudokuValue::Nine]
+            )))
+        );
+        assert_eq!(Action::from_str("undo"), Ok(Action::Undo));
+        assert_eq!(Action::from_str("R"), Ok(Action::Redo));
+        assert_eq!(Action::from_str("quit"), Ok(Action::Quit));
+        assert_eq!(Action::from_str("Hint"), Ok(Action::Hint));
+        assert_eq!(Action::from_str("c"), Ok(Action::Check));
+
+        for input in [
+            "", "r3c5", "r3c5=", "r3c5=12", "r3c5=1,2", "r3c5+0", "r0c5=1", "r3c5*1", "hints",
+        ] {
+            assert_eq!(
+                Action::from_str(input),
+                Err(PlayError::InvalidInput {
+                    input: input.to_string()
+                }),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn move_round_trip() {
+        for text in ["r3c5=7", "r3c5=0", "r1c9+1,2,3", "r9c1-4"] {
+            assert_matches!(Action::from_str(text), Ok(Action::Move(player_move)) => {
+                assert_eq!(player_move.to_string(), text);
+            });
+        }
+    }
+
+    #[test]
+    fn play() {
+        // Setup
+        let mut game = game(SDM);
+
+        // Execute
+        game.play(&Move::Mark(
+            cell("r1c4"),
+            vec![SudokuValue::Two, SudokuValue::Five],
+        ))
+        .unwrap();
+        game.play(&Move::Unmark(cell("r1c4"), vec![SudokuValue::Two]))
+            .unwrap();
+        game.play(&Move::Place(cell("r1c5"), SudokuValue::Two))
+            .unwrap();
+
+        // Verify
+        assert_eq!(game.board().value(&cell("r1c5")), SudokuValue::Two);
+        assert_eq!(
+            game.pencil_marks()
+                .values(&cell("r1c4"))
+                .collect::<Vec<_>>(),
+            [SudokuValue::Five]
+        );
+        assert!(game.board().is_valid());
+        assert!(!game.is_solved());
+        assert_eq!(
+            game.play(&Move::Place(cell("r1c1"), SudokuValue::One)),
+            Err(PlayError::Given { cell: cell("r1c1") })
+        );
+        assert_eq!(
+            game.play(&Move::Mark(cell("r1c5"), vec![SudokuValue::One])),
+            Err(PlayError::Filled { cell: cell("r1c5") })
+        );
+        assert_eq!(
+            PlayError::Filled { cell: cell("r1c5") }.to_string(),
+            "r1c5 already holds a digit"
+        );
+    }
+
+    #[test]
+    fn undo_redo() {
+        // Setup
+        let mut game = game(SDM);
+        game.play(&Move::Place(cell("r1c4"), SudokuValue::Five))
+            .unwrap();
+        game.play(&Move::Mark(cell("r1c5"), vec![SudokuValue::Two]))
+            .unwrap();
+
+        // Execute & Verify
+        game.undo().unwrap();
+        assert!(game.pencil_marks().is_empty());
+        game.undo().unwrap();
+        assert_eq!(game.board().value(&cell("r1c4")), SudokuValue::Unknown);
+        assert_eq!(game.undo(), Err(PlayError::NothingToUndo));
+
+        game.redo().unwrap();
+        assert_eq!(game.board().value(&cell("r1c4")), SudokuValue::Five);
+        assert!(game.pencil_marks().is_empty());
+
+        // A new move drops the moves which were undone.
+        game.play(&Move::Place(cell("r1c5"), SudokuValue::Two))
+            .unwrap();
+        assert_eq!(game.redo(), Err(PlayError::NothingToRedo));
+        game.undo().unwrap();
+        assert_eq!(game.board().value(&cell("r1c5")), SudokuValue::Unknown);
+        assert_eq!(game.board().value(&cell("r1c4")), SudokuValue::Five);
+    }
+
+    #[test]
+    fn conflicts() {
+        // Setup
+        let mut game = game(SDM).with_colour(true);
+
+        // Execute
+        game.play(&Move::Place(cell("r1c4"), SudokuValue::Four))
+            .unwrap();
+
+        // Verify
+        assert!(!game.board().is_valid());
+        // The 4 repeats the one in its row.
+        assert_eq!(game.board().conflicts(), [cell("r1c4"), cell("r1c9")]);
+        let line = game.render().lines().nth(1).unwrap().to_string();
+        assert!(line.contains("\x1b[31m4\x1b[0m"), "{}", line);
+        assert!(!line.contains("\x1b[1m4\x1b[0m"), "{}", line);
+
+        // Clearing the cell again resolves the conflict.
+        game.play(&Move::Place(cell("r1c4"), SudokuValue::Unknown))
+            .unwrap();
+        assert!(game.board().is_valid());
+        assert!(game.board().conflicts().is_empty());
+    }
+
+    #[test]
+    fn run() {
+        // Setup
+        let mut sdm = SOLUTION.to_string();
+        sdm.replace_range(3..5, "00");
+        let mut game = game(&sdm);
+        let input = [
+            "r1c4=2", "", "r1c4=1", "r1c1=1", "r1c5+2", "undo", "nonsense", "r1c4=5", "r1c5=2",
+            "r1c5=2",
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+
+        // Execute
+        let solved = game.run(Cursor::new(input), &mut output).unwrap();
+
+        // Verify
+        assert!(solved);
+        assert!(game.is_solved());
+        assert_eq!(format!("{:?}", game.board()), SOLUTION);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with(USAGE));
+        assert!(
+            output.contains("conflicts: r1c4 r1c8 r2c5 r5c4"),
+            "{}",
+            output
+        );
+        assert!(output.contains("> r1c1 is a given\n"), "{}", output);
+        assert!(output.contains("> invalid input 'nonsense'"), "{}", output);
+        // The input stops being read once the puzzle is solved.
+        assert!(output.ends_with("solved!\n"), "{}", output);
+        assert_eq!(output.matches("> ").count(), 9);
+    }
+
+    #[test]
+    fn run_quit() {
+        let mut game = game(SDM);
+        let mut output = Vec::new();
+
+        let solved = game
+            .run(Cursor::new("r1c4=5\nquit\nr1c5=2\n"), &mut output)
+            .unwrap();
+
+        assert!(!solved);
+        assert_eq!(game.board().value(&cell("r1c4")), SudokuValue::Five);
+        assert_eq!(game.board().value(&cell("r1c5")), SudokuValue::Unknown);
+    }
+
+    #[test]
+    fn autoplay() {
+        // Setup
+        let mut game = game(SDM);
+        let solution = SudokuProblem::from_str(SOLUTION).unwrap();
+        let mut output = Vec::new();
+
+        // Execute
+        let solved = game.autoplay(&solution, &mut output).unwrap();
+
+        // Verify
+        assert!(solved);
+        assert_eq!(game.board(), &solution);
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines[..3], ["r1c4=5", "r1c5=2", "r1c6=6"]);
+        // A move per unknown cell, then the board & its status.
+        assert_eq!(lines.len(), SDM.matches('0').count() + 13 + 1);
+        assert_eq!(lines.last(), Some(&"solved!"));
+    }
+
+    fn assisted(sdm: &str) -> Game {
+        game(sdm).with_solution(SudokuProblem::from_str(SOLUTION).unwrap())
+    }
+
+    #[test]
+    fn hint() {
+        // Setup
+        let mut game = assisted(SDM);
+
+        // Execute
+        let hints = (0..4).map(|_| game.hint().unwrap()).collect::<Vec<_>>();
+
+        // Verify
+        assert_eq!(hints[0], Hint::Region(3));
+        assert_eq!(hints[1], Hint::Techniques(vec![Technique::NakedSingle]));
+        assert_eq!(hints[2], Hint::Placement(cell("r3c8"), SudokuValue::Six));
+        assert_eq!(hints[3], hints[2]);
+        assert_eq!(hints[0].to_string(), "look at box 3");
+        assert_eq!(hints[1].to_string(), "try a Naked Single");
+        assert_eq!(hints[2].to_string(), "r3c8 is a 6");
+
+        // A move starts the hints over again.
+        game.play(&Move::Place(cell("r3c8"), SudokuValue::Six))
+            .unwrap();
+        assert!(matches!(game.hint(), Ok(Hint::Region(_))));
+    }
+
+    #[test]
+    fn hint_ignores_wrong_digits() {
+        // Setup
+        let mut game = assisted(SDM);
+        game.play(&Move::Place(cell("r3c8"), SudokuValue::Three))
+            .unwrap();
+
+        // Execute
+        let hints = (0..3).map(|_| game.hint().unwrap()).collect::<Vec<_>>();
+
+        // Verify
+        assert_eq!(hints[2], Hint::Placement(cell("r3c8"), SudokuValue::Six));
+    }
+
+    #[test]
+    fn hint_solved() {
+        let mut game = assisted(SOLUTION);
+        assert_eq!(game.hint(), Err(PlayError::Solved));
+        assert_eq!(
+            game.hint().unwrap_err().to_string(),
+            "the puzzle is already solved"
+        );
+    }
+
+    #[test]
+    fn hint_techniques() {
+        let techniques = Hint::Techniques(vec![Technique::PointingPair, Technique::HiddenSingle]);
+        assert_eq!(
+            techniques.to_string(),
+            "try a Pointing Pair, then a Hidden Single"
+        );
+        assert_eq!(
+            Hint::Techniques(Vec::default()).to_string(),
+            "none of the techniques apply, so it will take trial & error"
+        );
+    }
+
+    #[test]
+    fn check() {
+        // Setup
+        let mut game = assisted(SDM);
+        for (text, value) in [
+            ("r1c4", SudokuValue::Five),
+            ("r1c5", SudokuValue::Three),
+            ("r9c9", SudokuValue::Nine),
+            ("r2c1", SudokuValue::Eight),
+        ] {
+            game.play(&Move::Place(cell(text), value)).unwrap_or(());
+        }
+
+        // Execute
+        let wrong = game.check().unwrap();
+
+        // Verify
+        // The right digit at r1c4 & the given at r9c9 are left out.
+        assert_eq!(wrong, vec![cell("r1c5"), cell("r2c1")]);
+    }
+
+    #[test]
+    fn unassisted() {
+        let mut game = game(SDM);
+        assert_eq!(game.hint(), Err(PlayError::Unassisted));
+        assert_eq!(game.check(), Err(PlayError::Unassisted));
+    }
+
+    #[test]
+    fn run_assisted() {
+        // Setup
+        let mut game = assisted(SDM);
+        let mut output = Vec::new();
+
+        // Execute
+        game.run(Cursor::new("hint\nr1c5=3\ncheck\nquit\n"), &mut output)
+            .unwrap();
+
+        // Verify
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(ASSISTANCE), "{}", output);
+        assert!(output.contains("> look at box 3\n"), "{}", output);
+        assert!(output.contains("> wrong: r1c5\n"), "{}", output);
+    }
+}
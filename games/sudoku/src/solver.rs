@@ -1 +1,395 @@
+use crate::model::board::SudokuProblem;
+use crate::solver::dlx::Dlx;
+use crate::solver::propagation::Propagation;
+use crate::solver::search::{Search, SearchError};
+
+pub mod dlx;
+pub mod logical;
+pub mod propagation;
+pub mod samurai;
 pub mod search;
+
+pub trait Solver {
+    // Find up to `limit` distinct solutions.
+    // Fails when a budget runs out before the search is finished, since the solutions found so far prove nothing.
+    fn solutions(&self, limit: usize) -> Result<Vec<SudokuProblem>, SearchError>;
+
+    fn run(&self) -> Result<SudokuProblem, SearchError> {
+        self.solutions(1)?.pop().ok_or(SearchError::NoSolution)
+    }
+
+    fn count(&self, limit: usize) -> Result<usize, SearchError> {
+        Ok(self.solutions(limit)?.len())
+    }
+
+    fn uniqueness(&self) -> Result<Uniqueness, SearchError> {
+        Ok(Uniqueness::from_solutions(self.solutions(2)?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Uniqueness {
+    NoSolution,
+    Unique(Box<SudokuProblem>),
+    Multiple,
+}
+
+impl Uniqueness {
+    pub fn from_solutions(mut solutions: Vec<SudokuProblem>) -> Self {
+        match solutions.len() {
+            0 => Uniqueness::NoSolution,
+            1 => Uniqueness::Unique(Box::new(solutions.pop().expect("must have a solution"))),
+            _ => Uniqueness::Multiple,
+        }
+    }
+}
+
+impl std::fmt::Display for Uniqueness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Uniqueness::NoSolution => write!(f, "none"),
+            Uniqueness::Unique(_) => write!(f, "unique"),
+            Uniqueness::Multiple => write!(f, "multiple"),
+        }
+    }
+}
+
+const SOLVER_TYPES: [SolverType; 3] = [
+    SolverType::DepthFirstSearch,
+    SolverType::ConstraintPropagation,
+    SolverType::DancingLinks,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverType {
+    DepthFirstSearch,
+    ConstraintPropagation,
+    DancingLinks,
+}
+
+impl SolverType {
+    pub fn all() -> &'static [SolverType] {
+        &SOLVER_TYPES[..]
+    }
+
+    pub fn build(&self, problem: SudokuProblem) -> Box<dyn Solver> {
+        match self {
+            SolverType::DepthFirstSearch => Box::new(Search::new(problem)),
+            SolverType::ConstraintPropagation => Box::new(Propagation::new(problem)),
+            SolverType::DancingLinks => Box::new(Dlx::new(problem)),
+        }
+    }
+}
+
+impl std::str::FromStr for SolverType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "dfs" | "depth-first-search" => Ok(SolverType::DepthFirstSearch),
+            "propagation" | "constraint-propagation" => Ok(SolverType::ConstraintPropagation),
+            "dlx" | "dancing-links" => Ok(SolverType::DancingLinks),
+            _ => Err(format!("unknown: {}", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for SolverType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverType::DepthFirstSearch => write!(f, "depth-first-search"),
+            SolverType::ConstraintPropagation => write!(f, "constraint-propagation"),
+            SolverType::DancingLinks => write!(f, "dancing-links"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::constraint::Constraint;
+    use crate::model::format::Format;
+    use crate::model::jigsaw::Jigsaw;
+    use crate::model::variant::Variant;
+    use std::str::FromStr;
+
+    #[test]
+    fn solver_type_round_trip() {
+        for solver_type in SolverType::all() {
+            assert_eq!(
+                SolverType::from_str(&solver_type.to_string()).unwrap(),
+                *solver_type
+            );
+        }
+
+        assert_eq!(
+            SolverType::from_str("dfs").unwrap(),
+            SolverType::DepthFirstSearch
+        );
+        assert_eq!(
+            SolverType::from_str("propagation").unwrap(),
+            SolverType::ConstraintPropagation
+        );
+        assert_eq!(
+            SolverType::from_str("DLX").unwrap(),
+            SolverType::DancingLinks
+        );
+        SolverType::from_str("bogo").unwrap_err();
+    }
+
+    #[test]
+    fn solvers_agree() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let expected = SudokuProblem::from_str(
+            "379526814564318972281479365435267198698143527712895436923754681146982753857631249",
+        )
+        .unwrap();
+
+        for solver_type in SolverType::all() {
+            // Execute
+            let result = solver_type.build(problem.clone()).run().unwrap();
+
+            // Verify
+            assert_eq!(result, expected, "{}", solver_type);
+        }
+    }
+
+    #[test]
+    fn uniqueness() {
+        let unique = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let solution = SudokuProblem::from_str(
+            "379526814564318972281479365435267198698143527712895436923754681146982753857631249",
+        )
+        .unwrap();
+        // The solution, with a rectangle of 3s & 5s (r1c1, r1c4, r2c1, r2c4) removed.
+        // These may be swapped, so the puzzle has exactly 2 solutions.
+        let multiple = SudokuProblem::from_str(
+            "079026814064018972281479365435267198698143527712895436923754681146982753857631249",
+        )
+        .unwrap();
+        let none = SudokuProblem::from_str(
+            "012345678900000000000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+
+        for solver_type in SolverType::all() {
+            assert_eq!(
+                solver_type.build(unique.clone()).uniqueness().unwrap(),
+                Uniqueness::Unique(Box::new(solution.clone())),
+                "{}",
+                solver_type
+            );
+            assert_eq!(
+                solver_type.build(multiple.clone()).uniqueness().unwrap(),
+                Uniqueness::Multiple,
+                "{}",
+                solver_type
+            );
+            assert_eq!(
+                solver_type.build(none.clone()).uniqueness().unwrap(),
+                Uniqueness::NoSolution,
+                "{}",
+                solver_type
+            );
+        }
+    }
+
+    #[test]
+    fn count() {
+        // Each solver enumerates the same number of solutions.
+        let problem = SudokuProblem::from_str(
+            "079026814064018972281479365435267198698143527712895436923754681146982753857631249",
+        )
+        .unwrap();
+
+        for solver_type in SolverType::all() {
+            let solver = solver_type.build(problem.clone());
+            assert_eq!(solver.count(0), Ok(0), "{}", solver_type);
+            assert_eq!(solver.count(1), Ok(1), "{}", solver_type);
+            assert_eq!(solver.count(10), Ok(2), "{}", solver_type);
+        }
+
+        let empty = SudokuProblem::default();
+
+        for solver_type in SolverType::all() {
+            assert_eq!(
+                solver_type.build(empty.clone()).count(50).unwrap(),
+                50,
+                "{}",
+                solver_type
+            );
+        }
+    }
+
+    #[test]
+    fn solvers_honour_variants() {
+        let puzzles = [
+            (
+                Variant::X,
+                "001739000500000600000050000040081090008090300020670040000060000006000008000417900",
+            ),
+            (
+                Variant::Hyper,
+                "800000109400003000031000406000000600610000054005000000907000840000100002103000005",
+            ),
+        ];
+
+        for (variant, sdm) in puzzles {
+            // Setup
+            let classic = SudokuProblem::from_str(sdm).unwrap();
+            let problem = classic.with_variant(variant).unwrap();
+            let solution = Dlx::new(problem.clone()).run().unwrap();
+
+            for solver_type in SolverType::all() {
+                // Execute
+                let uniqueness = solver_type.build(problem.clone()).uniqueness().unwrap();
+
+                // Verify
+                assert_eq!(
+                    uniqueness,
+                    Uniqueness::Unique(Box::new(solution.clone())),
+                    "{} {}",
+                    variant,
+                    solver_type
+                );
+            }
+
+            // Without the extra regions, the puzzle has many solutions.
+            assert_eq!(
+                Dlx::new(classic).uniqueness().unwrap(),
+                Uniqueness::Multiple
+            );
+
+            for region in solution.regions() {
+                let mut values = region
+                    .iter()
+                    .map(|cell| solution.value(cell).to_count_index().unwrap())
+                    .collect::<Vec<_>>();
+                values.sort_unstable();
+                assert_eq!(values, (0..9).collect::<Vec<_>>(), "{}", variant);
+            }
+        }
+    }
+
+    #[test]
+    fn solvers_honour_jigsaw() {
+        // Setup
+        let jigsaw = Jigsaw::from_str(
+            &[
+                "aaabbbccc",
+                "aabbbcccc",
+                "daaabeecc",
+                "ddabbefff",
+                "dddeeefff",
+                "dggehhfff",
+                "ddgeehiii",
+                "ggghhhiii",
+                "ggghhhiii",
+            ]
+            .concat(),
+        )
+        .unwrap();
+        let layout = SudokuProblem::default().with_jigsaw(&jigsaw).unwrap();
+        let problem = Format::Sdm
+            .parse_with(
+                "060000850785004006830000010304070209270900000498000075007002500620030008000798000",
+                &layout,
+            )
+            .unwrap();
+
+        for solver_type in SolverType::all() {
+            // Execute
+            let uniqueness = solver_type.build(problem.clone()).uniqueness().unwrap();
+
+            // Verify
+            let solution = assert_matches!(uniqueness, Uniqueness::Unique(solution) => solution);
+            assert_eq!(
+                format!("{:?}", solution),
+                "162349857785124936839567412354871269276953184498216375917682543621435798543798621",
+                "{}",
+                solver_type
+            );
+
+            for region in solution.squares() {
+                let mut values = region
+                    .iter()
+                    .map(|value| value.to_count_index().unwrap())
+                    .collect::<Vec<_>>();
+                values.sort_unstable();
+                assert_eq!(values, (0..9).collect::<Vec<_>>(), "{}", solver_type);
+            }
+        }
+    }
+
+    #[test]
+    fn solvers_honour_constraints() {
+        // The puzzles share the solution of the Miracle Sudoku, which satisfies all 3 constraints.
+        let miracle =
+            "483726159726159483159483726837261594261594837594837261372615948615948372948372615";
+        let puzzles = [
+            (
+                Constraint::AntiKnight,
+                "000700109000009400100480000830001000200590837590000200002015000000900070900072015",
+            ),
+            (
+                Constraint::AntiKing,
+                "000720100000009400100480000830001000200590837590000200002015000000900070900072015",
+            ),
+            (
+                Constraint::NonConsecutive,
+                "000700100000009400100480000830001000200590837590000200002015000005900070900072015",
+            ),
+        ];
+
+        for (constraint, sdm) in puzzles {
+            // Setup
+            let classic = SudokuProblem::from_str(sdm).unwrap();
+            let problem = classic.with_constraint(constraint).unwrap();
+
+            for solver_type in SolverType::all() {
+                // Execute
+                let uniqueness = solver_type.build(problem.clone()).uniqueness().unwrap();
+
+                // Verify
+                let solution =
+                    assert_matches!(uniqueness, Uniqueness::Unique(solution) => solution);
+                assert_eq!(
+                    format!("{:?}", solution),
+                    miracle,
+                    "{} {}",
+                    constraint,
+                    solver_type
+                );
+            }
+
+            // Without the constraint, the puzzle has many solutions.
+            assert_eq!(
+                Dlx::new(classic).uniqueness().unwrap(),
+                Uniqueness::Multiple
+            );
+        }
+
+        // The Miracle Sudoku itself: all 3 constraints, with only 2 givens.
+        let mut problem = SudokuProblem::from_str(
+            "000000000000000000000000000000000000001000000000000200000000000000000000000000000",
+        )
+        .unwrap();
+
+        for constraint in Constraint::all() {
+            problem = problem.with_constraint(*constraint).unwrap();
+        }
+
+        // The depth first search is too slow without the propagation of the candidates.
+        for solver_type in [SolverType::ConstraintPropagation, SolverType::DancingLinks] {
+            let solution = assert_matches!(solver_type.build(problem.clone()).uniqueness().unwrap(), Uniqueness::Unique(solution) => solution);
+            assert_eq!(format!("{:?}", solution), miracle, "{}", solver_type);
+        }
+    }
+}
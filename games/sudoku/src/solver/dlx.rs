@@ -0,0 +1,397 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::unit::Units;
+use crate::solver::search::SearchError;
+use crate::solver::Solver;
+
+// The choices (rows of the matrix), one for each digit in each cell.
+const OPTIONS: usize = 9 * 9 * 9;
+const ROOT: usize = 0;
+
+#[derive(Debug)]
+pub struct Dlx {
+    problem: SudokuProblem,
+}
+
+impl Dlx {
+    pub fn new(problem: SudokuProblem) -> Dlx {
+        Self { problem }
+    }
+}
+
+impl Solver for Dlx {
+    fn solutions(&self, limit: usize) -> Result<Vec<SudokuProblem>, SearchError> {
+        let mut solutions = Vec::new();
+
+        if !self.problem.is_valid() || limit == 0 {
+            return Ok(solutions);
+        }
+
+        let mut matrix = Matrix::new(self.problem.units());
+
+        for index in 0..9 * 9 {
+            let cell = Cell::index(index).expect("must be a valid index");
+
+            if let Some(digit) = self.problem.value(&cell).to_count_index() {
+                let option = (index * 9) + digit;
+                matrix.select(matrix.first[option]);
+            }
+        }
+
+        matrix.search(
+            &mut Vec::with_capacity(9 * 9),
+            &mut |options: &[usize]| {
+                let mut result = self.problem.clone();
+
+                for option in options {
+                    let cell = Cell::index(option / 9).expect("must be a valid index");
+                    result = result.replace(&cell, SudokuValue::candidates()[option % 9]);
+                }
+
+                solutions.push(result);
+                solutions.len() < limit
+            },
+        );
+
+        Ok(solutions)
+    }
+}
+
+// A toroidal doubly linked exact cover matrix, laid out as parallel arrays.
+// Node `0` is the root, nodes `1..=constraints` are the column headers, and the remaining nodes are the 1s of the matrix.
+// The exact cover constraints (columns of the matrix) are, in blocks of 81:
+// * every cell holds a value.
+// * every unit (row, column, square, then any extra region) holds each digit.
+// These are followed by a secondary column for each pair of options which the constraints beyond the units forbid together.
+// The secondary columns may be covered at most once, rather than exactly once, so are left out of the list of headers to choose from.
+struct Matrix {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    header: Vec<usize>,
+    option: Vec<usize>,
+    size: Vec<usize>,
+    first: Vec<usize>,
+}
+
+impl Matrix {
+    fn new(units: &Units) -> Self {
+        let primaries = (9 * 9) + (units.units.len() * 9);
+        let conflicts = conflicts(units);
+        let constraints = primaries + conflicts.len();
+        // Every primary constraint is satisfied by 9 options, and every secondary one by 2.
+        let capacity = 1 + constraints + (primaries * 9) + (conflicts.len() * 2);
+        let mut secondaries = vec![Vec::default(); OPTIONS];
+
+        for (i, (option, other)) in conflicts.iter().enumerate() {
+            secondaries[*option].push(primaries + i + 1);
+            secondaries[*other].push(primaries + i + 1);
+        }
+
+        let mut matrix = Self {
+            left: Vec::with_capacity(capacity),
+            right: Vec::with_capacity(capacity),
+            up: Vec::with_capacity(capacity),
+            down: Vec::with_capacity(capacity),
+            header: Vec::with_capacity(capacity),
+            option: Vec::with_capacity(capacity),
+            size: vec![0; 1 + constraints],
+            first: Vec::with_capacity(OPTIONS),
+        };
+
+        for node in 0..=constraints {
+            if node > primaries {
+                matrix.left.push(node);
+                matrix.right.push(node);
+            } else {
+                matrix
+                    .left
+                    .push(if node == ROOT { primaries } else { node - 1 });
+                matrix
+                    .right
+                    .push(if node == primaries { ROOT } else { node + 1 });
+            }
+
+            matrix.up.push(node);
+            matrix.down.push(node);
+            matrix.header.push(node);
+            matrix.option.push(OPTIONS);
+        }
+
+        for index in 0..9 * 9 {
+            for digit in 0..9 {
+                let option = (index * 9) + digit;
+                matrix.first.push(matrix.left.len());
+                matrix.append(option, index + 1, true);
+
+                for unit in units.cell_units[index].iter() {
+                    matrix.append(option, (9 * 9) + (unit * 9) + digit + 1, false);
+                }
+
+                for header in secondaries[option].iter() {
+                    matrix.append(option, *header, false);
+                }
+            }
+        }
+
+        matrix
+    }
+
+    fn append(&mut self, option: usize, header: usize, first: bool) {
+        let node = self.left.len();
+
+        if first {
+            self.left.push(node);
+            self.right.push(node);
+        } else {
+            let start = self.first[option];
+            let last = self.left[start];
+            self.left.push(last);
+            self.right.push(start);
+            self.right[last] = node;
+            self.left[start] = node;
+        }
+
+        let bottom = self.up[header];
+        self.up.push(bottom);
+        self.down.push(header);
+        self.down[bottom] = node;
+        self.up[header] = node;
+        self.header.push(header);
+        self.option.push(option);
+        self.size[header] += 1;
+    }
+
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        let mut i = self.down[header];
+
+        while i != header {
+            let mut j = self.right[i];
+
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.header[j]] -= 1;
+                j = self.right[j];
+            }
+
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+
+        while i != header {
+            let mut j = self.left[i];
+
+            while j != i {
+                self.size[self.header[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+
+            i = self.up[i];
+        }
+
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    // Commit to the option of `node`, covering every constraint it satisfies.
+    fn select(&mut self, node: usize) {
+        self.cover(self.header[node]);
+        let mut j = self.right[node];
+
+        while j != node {
+            self.cover(self.header[j]);
+            j = self.right[j];
+        }
+    }
+
+    fn deselect(&mut self, node: usize) {
+        let mut j = self.left[node];
+
+        while j != node {
+            self.uncover(self.header[j]);
+            j = self.left[j];
+        }
+
+        self.uncover(self.header[node]);
+    }
+
+    // The uncovered column with the fewest remaining options, or `None` when every constraint is satisfied.
+    fn choose(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        let mut header = self.right[ROOT];
+
+        while header != ROOT {
+            if best.is_none_or(|b| self.size[header] < self.size[b]) {
+                best = Some(header);
+            }
+
+            header = self.right[header];
+        }
+
+        best
+    }
+
+    // Search for exact covers, reporting each one to `found` until it asks to stop (by returning `false`).
+    // Returns `false` once the search has been stopped.
+    fn search(
+        &mut self,
+        solution: &mut Vec<usize>,
+        found: &mut impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        let header = match self.choose() {
+            Some(header) => header,
+            None => return found(solution),
+        };
+
+        let mut node = self.down[header];
+
+        while node != header {
+            self.select(node);
+            solution.push(self.option[node]);
+            let proceed = self.search(solution, found);
+            solution.pop();
+            self.deselect(node);
+
+            if !proceed {
+                return false;
+            }
+
+            node = self.down[node];
+        }
+
+        true
+    }
+}
+
+// The pairs of options which the constraints beyond the units forbid together:
+// the same digit in peers which share no unit, and consecutive digits in neighbours.
+fn conflicts(units: &Units) -> Vec<(usize, usize)> {
+    let mut conflicts = Vec::new();
+
+    for index in 0..9 * 9 {
+        for peer in units.peers[index]
+            .iter()
+            .filter(|peer| **peer > index && !units.share_unit(index, **peer))
+        {
+            for digit in 0..9 {
+                conflicts.push(((index * 9) + digit, (peer * 9) + digit));
+            }
+        }
+
+        for neighbour in units.neighbours[index]
+            .iter()
+            .filter(|neighbour| **neighbour > index)
+        {
+            for digit in 0..8 {
+                conflicts.push(((index * 9) + digit, (neighbour * 9) + digit + 1));
+                conflicts.push(((index * 9) + digit + 1, (neighbour * 9) + digit));
+            }
+        }
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::search::Search;
+    use std::str::FromStr;
+
+    #[test]
+    fn dlx() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let dlx = Dlx::new(problem);
+
+        // Execute
+        let result = dlx.run().unwrap();
+
+        // Verify
+        assert_eq!(
+            result,
+            SudokuProblem::from_str(
+                "379526814564318972281479365435267198698143527712895436923754681146982753857631249"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn dlx_matches_search() {
+        for sdm in [
+            "016400000200009000400000062070230100100000003003087040960000005000800007000006820",
+            "049008605003007000000000030000400800060815020001009000010000000000600400804500390",
+            "760500000000060008000000403200400800080000030005001007809000000600010000000003041",
+        ] {
+            // Setup
+            let problem = SudokuProblem::from_str(sdm).unwrap();
+
+            // Execute
+            let dlx = Dlx::new(problem.clone()).run().unwrap();
+            let search = Search::new(problem).run().unwrap();
+
+            // Verify
+            assert_eq!(dlx, search);
+        }
+    }
+
+    #[test]
+    fn dlx_hard() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        )
+        .unwrap();
+        let dlx = Dlx::new(problem);
+
+        // Execute
+        let result = dlx.run().unwrap();
+
+        // Verify
+        assert_eq!(
+            result,
+            SudokuProblem::from_str(
+                "812753649943682175675491283154237896369845721287169534521974368438526917796318452"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn dlx_empty() {
+        let result = Dlx::new(SudokuProblem::default()).run().unwrap();
+        assert!(result.is_complete());
+        assert!(result.is_valid());
+    }
+
+    #[test]
+    fn dlx_unsolvable() {
+        // Duplicate values.
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap()
+        .replace(&Cell::row_column(0, 3).unwrap(), SudokuValue::Three);
+        assert_matches!(Dlx::new(problem).run(), Err(SearchError::NoSolution));
+
+        // The first cell has no candidates: 1-8 are in its row, 9 is in its column.
+        let problem = SudokuProblem::from_str(
+            "012345678900000000000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        assert_matches!(Dlx::new(problem).run(), Err(SearchError::NoSolution));
+    }
+}
@@ -0,0 +1,1113 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::pencil::PencilMarks;
+use crate::model::unit::Units;
+use std::collections::VecDeque;
+
+// Ordered from the easiest to the hardest technique.
+const TECHNIQUES: [Technique; 12] = [
+    Technique::NakedSingle,
+    Technique::HiddenSingle,
+    Technique::NakedPair,
+    Technique::HiddenPair,
+    Technique::PointingPair,
+    Technique::BoxLineReduction,
+    Technique::NakedTriple,
+    Technique::HiddenTriple,
+    Technique::XWing,
+    Technique::SimpleColoring,
+    Technique::XYWing,
+    Technique::Swordfish,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    NakedPair,
+    HiddenPair,
+    PointingPair,
+    BoxLineReduction,
+    NakedTriple,
+    HiddenTriple,
+    XWing,
+    SimpleColoring,
+    XYWing,
+    Swordfish,
+}
+
+impl Technique {
+    pub fn all() -> &'static [Technique] {
+        &TECHNIQUES[..]
+    }
+}
+
+impl std::fmt::Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Technique::NakedSingle => write!(f, "Naked Single"),
+            Technique::HiddenSingle => write!(f, "Hidden Single"),
+            Technique::NakedPair => write!(f, "Naked Pair"),
+            Technique::HiddenPair => write!(f, "Hidden Pair"),
+            Technique::PointingPair => write!(f, "Pointing Pair"),
+            Technique::BoxLineReduction => write!(f, "Box/Line Reduction"),
+            Technique::NakedTriple => write!(f, "Naked Triple"),
+            Technique::HiddenTriple => write!(f, "Hidden Triple"),
+            Technique::XWing => write!(f, "X-Wing"),
+            Technique::SimpleColoring => write!(f, "Simple Coloring"),
+            Technique::XYWing => write!(f, "XY-Wing"),
+            Technique::Swordfish => write!(f, "Swordfish"),
+        }
+    }
+}
+
+// A single deduction.
+// `cells` & `values` describe the pattern the technique found, which justifies the eliminations or placement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    pub cells: Vec<Cell>,
+    pub values: Vec<SudokuValue>,
+    pub eliminations: Vec<(Cell, SudokuValue)>,
+    pub placement: Option<(Cell, SudokuValue)>,
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.technique)?;
+
+        match &self.placement {
+            Some((cell, value)) => write!(f, ": {} = {:?}", cell, value),
+            None => {
+                let values = self
+                    .values
+                    .iter()
+                    .map(|value| format!("{:?}", value))
+                    .collect::<Vec<_>>();
+                let cells = self
+                    .cells
+                    .iter()
+                    .map(|cell| cell.to_string())
+                    .collect::<Vec<_>>();
+                let eliminations = self
+                    .eliminations
+                    .iter()
+                    .map(|(cell, value)| format!("{} <> {:?}", cell, value))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    " {{{}}} at {}: {}",
+                    values.join(","),
+                    cells.join(", "),
+                    eliminations.join(", ")
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SolvePath {
+    steps: Vec<Step>,
+    result: SudokuProblem,
+    pencil_marks: PencilMarks,
+}
+
+impl SolvePath {
+    pub fn steps(&self) -> &[Step] {
+        &self.steps[..]
+    }
+
+    // The problem after applying every step; only complete when `is_solved`.
+    pub fn result(&self) -> &SudokuProblem {
+        &self.result
+    }
+
+    // The candidates left in the unknown cells of the result.
+    pub fn pencil_marks(&self) -> &PencilMarks {
+        &self.pencil_marks
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.result.is_valid() && self.result.is_complete()
+    }
+}
+
+// Solve a problem using only the named human techniques, applying the easiest available technique at each step.
+// As an `Iterator`, each call to `next` finds and applies the next step.
+#[derive(Debug, Clone)]
+pub struct Logical {
+    problem: SudokuProblem,
+    pencil_marks: PencilMarks,
+}
+
+impl Logical {
+    pub fn new(problem: SudokuProblem) -> Logical {
+        let pencil_marks = PencilMarks::from_problem(&problem);
+        Self {
+            problem,
+            pencil_marks,
+        }
+    }
+
+    pub fn run(&self) -> SolvePath {
+        let mut logical = self.clone();
+        let steps = logical.by_ref().collect();
+        SolvePath {
+            steps,
+            result: logical.problem,
+            pencil_marks: logical.pencil_marks,
+        }
+    }
+
+    pub fn problem(&self) -> &SudokuProblem {
+        &self.problem
+    }
+
+    pub fn pencil_marks(&self) -> &PencilMarks {
+        &self.pencil_marks
+    }
+
+    fn find_step(&self, technique: Technique) -> Option<Step> {
+        match technique {
+            Technique::NakedSingle => self.find_naked_single(),
+            Technique::HiddenSingle => self.find_hidden_single(),
+            Technique::NakedPair => self.find_naked_subset(technique, 2),
+            Technique::HiddenPair => self.find_hidden_subset(technique, 2),
+            Technique::PointingPair => self.find_pointing_pair(),
+            Technique::BoxLineReduction => self.find_box_line_reduction(),
+            Technique::NakedTriple => self.find_naked_subset(technique, 3),
+            Technique::HiddenTriple => self.find_hidden_subset(technique, 3),
+            Technique::XWing => self.find_fish(technique, 2),
+            Technique::SimpleColoring => self.find_simple_coloring(),
+            Technique::XYWing => self.find_xy_wing(),
+            Technique::Swordfish => self.find_fish(technique, 3),
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        for (cell, value) in step.eliminations.iter() {
+            self.pencil_marks.remove(cell, *value);
+        }
+
+        if let Some((cell, value)) = step.placement {
+            self.problem = self.problem.replace(&cell, value);
+            self.pencil_marks.clear(&cell);
+
+            for peer in self.problem.units().peers[cell.index].iter() {
+                self.pencil_marks.remove(&as_cell(*peer), value);
+            }
+
+            // Under the non-consecutive constraint, the digits either side are eliminated from the neighbours.
+            let digit = value.to_count_index().expect("must be a digit");
+
+            for neighbour in self.problem.units().neighbours[cell.index].iter() {
+                for other in [digit.wrapping_sub(1), digit + 1] {
+                    if let Some(other) = SudokuValue::candidates().get(other) {
+                        self.pencil_marks.remove(&as_cell(*neighbour), *other);
+                    }
+                }
+            }
+        }
+    }
+
+    // The rows, columns & squares, plus any extra regions of the problem.
+    fn units(&self) -> &Units {
+        self.problem.units()
+    }
+
+    fn mask(&self, index: usize) -> u16 {
+        self.pencil_marks.mask(index)
+    }
+
+    // The cells of the unit which may hold the digit.
+    fn positions(&self, unit: usize, digit: usize) -> Vec<usize> {
+        self.units().units[unit]
+            .iter()
+            .copied()
+            .filter(|index| self.mask(*index) & (1 << digit) != 0)
+            .collect()
+    }
+
+    fn find_naked_single(&self) -> Option<Step> {
+        (0..9 * 9)
+            .find(|index| self.mask(*index).count_ones() == 1)
+            .map(|index| {
+                placement(
+                    Technique::NakedSingle,
+                    index,
+                    self.mask(index).trailing_zeros() as usize,
+                )
+            })
+    }
+
+    fn find_hidden_single(&self) -> Option<Step> {
+        for unit in 0..self.units().units.len() {
+            for digit in 0..9 {
+                let positions = self.positions(unit, digit);
+
+                if positions.len() == 1 {
+                    return Some(placement(Technique::HiddenSingle, positions[0], digit));
+                }
+            }
+        }
+
+        None
+    }
+
+    // `size` cells of a unit whose candidates are limited to `size` digits: those digits may be eliminated from the rest of the unit.
+    fn find_naked_subset(&self, technique: Technique, size: usize) -> Option<Step> {
+        for unit in self.units().units.iter() {
+            let cells: Vec<usize> = unit
+                .iter()
+                .copied()
+                .filter(|index| (2..=size).contains(&(self.mask(*index).count_ones() as usize)))
+                .collect();
+
+            for subset in combinations(&cells, size) {
+                let union = subset
+                    .iter()
+                    .fold(0, |mask, index| mask | self.mask(*index));
+
+                if union.count_ones() as usize != size {
+                    continue;
+                }
+
+                let eliminations = unit
+                    .iter()
+                    .filter(|index| !subset.contains(index))
+                    .flat_map(|index| digits(self.mask(*index) & union).map(move |d| (*index, d)))
+                    .collect();
+
+                if let Some(step) = elimination(
+                    technique,
+                    &subset,
+                    &digits(union).collect::<Vec<_>>(),
+                    eliminations,
+                ) {
+                    return Some(step);
+                }
+            }
+        }
+
+        None
+    }
+
+    // `size` digits of a unit which are limited to `size` cells: every other digit may be eliminated from those cells.
+    fn find_hidden_subset(&self, technique: Technique, size: usize) -> Option<Step> {
+        for unit in 0..self.units().units.len() {
+            let candidates: Vec<usize> = (0..9)
+                .filter(|digit| (2..=size).contains(&self.positions(unit, *digit).len()))
+                .collect();
+
+            for subset in combinations(&candidates, size) {
+                let keep = subset.iter().fold(0, |mask, digit| mask | (1 << digit));
+                let cells: Vec<usize> = self.units().units[unit]
+                    .iter()
+                    .copied()
+                    .filter(|index| self.mask(*index) & keep != 0)
+                    .collect();
+
+                if cells.len() != size {
+                    continue;
+                }
+
+                let eliminations = cells
+                    .iter()
+                    .flat_map(|index| digits(self.mask(*index) & !keep).map(move |d| (*index, d)))
+                    .collect();
+
+                if let Some(step) = elimination(technique, &cells, &subset, eliminations) {
+                    return Some(step);
+                }
+            }
+        }
+
+        None
+    }
+
+    // A digit which is limited to one row (or column) within a square: it may be eliminated from the rest of that row (or column).
+    fn find_pointing_pair(&self) -> Option<Step> {
+        for square in 18..27 {
+            for digit in 0..9 {
+                let positions = self.positions(square, digit);
+
+                if positions.len() < 2 {
+                    continue;
+                }
+
+                // Row (0) or column (1).
+                for kind in 0..2 {
+                    let line = self.units().cell_units[positions[0]][kind];
+
+                    if positions
+                        .iter()
+                        .all(|index| self.units().cell_units[*index][kind] == line)
+                    {
+                        let eliminations = self
+                            .positions(line, digit)
+                            .into_iter()
+                            .filter(|index| self.units().cell_units[*index][2] != square)
+                            .map(|index| (index, digit))
+                            .collect();
+
+                        if let Some(step) =
+                            elimination(Technique::PointingPair, &positions, &[digit], eliminations)
+                        {
+                            return Some(step);
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // A digit which is limited to one square within a row (or column): it may be eliminated from the rest of that square.
+    fn find_box_line_reduction(&self) -> Option<Step> {
+        for line in 0..18 {
+            for digit in 0..9 {
+                let positions = self.positions(line, digit);
+
+                if positions.len() < 2 {
+                    continue;
+                }
+
+                let square = self.units().cell_units[positions[0]][2];
+
+                if positions
+                    .iter()
+                    .all(|index| self.units().cell_units[*index][2] == square)
+                {
+                    let eliminations = self
+                        .positions(square, digit)
+                        .into_iter()
+                        .filter(|index| !self.units().units[line].contains(index))
+                        .map(|index| (index, digit))
+                        .collect();
+
+                    if let Some(step) = elimination(
+                        Technique::BoxLineReduction,
+                        &positions,
+                        &[digit],
+                        eliminations,
+                    ) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // `size` base rows in which a digit is limited to the same `size` columns: it may be eliminated from the rest of those columns.
+    // Likewise with the roles of rows and columns swapped.
+    // The X-Wing is the fish of size 2, while the Swordfish is the fish of size 3.
+    fn find_fish(&self, technique: Technique, size: usize) -> Option<Step> {
+        for digit in 0..9 {
+            // Rows (0) or columns (1).
+            for (base, cover) in [(0, 1), (1, 0)] {
+                let lines: Vec<usize> = (0..9)
+                    .map(|i| (base * 9) + i)
+                    .filter(|line| (2..=size).contains(&self.positions(*line, digit).len()))
+                    .collect();
+
+                for subset in combinations(&lines, size) {
+                    let cells: Vec<usize> = subset
+                        .iter()
+                        .flat_map(|line| self.positions(*line, digit))
+                        .collect();
+                    let mut covers: Vec<usize> = cells
+                        .iter()
+                        .map(|index| self.units().cell_units[*index][cover])
+                        .collect();
+                    covers.sort_unstable();
+                    covers.dedup();
+
+                    if covers.len() != size {
+                        continue;
+                    }
+
+                    let eliminations = covers
+                        .iter()
+                        .flat_map(|line| self.positions(*line, digit))
+                        .filter(|index| !subset.contains(&self.units().cell_units[*index][base]))
+                        .map(|index| (index, digit))
+                        .collect();
+
+                    if let Some(step) = elimination(technique, &cells, &[digit], eliminations) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // A pivot {x, y} which sees two pincers {x, z} & {y, z}: whichever value the pivot takes, one of the pincers must be z.
+    // So z may be eliminated from every cell which sees both pincers.
+    fn find_xy_wing(&self) -> Option<Step> {
+        for pivot in 0..9 * 9 {
+            let pivot_mask = self.mask(pivot);
+
+            if pivot_mask.count_ones() != 2 {
+                continue;
+            }
+
+            for a in self.units().peers[pivot].iter().copied() {
+                let a_mask = self.mask(a);
+
+                if a_mask.count_ones() != 2 || (a_mask & pivot_mask).count_ones() != 1 {
+                    continue;
+                }
+
+                let x = a_mask & pivot_mask;
+                let z = a_mask & !pivot_mask;
+                let b_mask = (pivot_mask & !x) | z;
+
+                for b in self.units().peers[pivot].iter().copied() {
+                    if b == a || self.mask(b) != b_mask {
+                        continue;
+                    }
+
+                    let z_digit = z.trailing_zeros() as usize;
+                    let eliminations = self.units().peers[a]
+                        .iter()
+                        .copied()
+                        .filter(|index| {
+                            self.units().peers[b].contains(index) && self.mask(*index) & z != 0
+                        })
+                        .map(|index| (index, z_digit))
+                        .collect();
+
+                    if let Some(step) = elimination(
+                        Technique::XYWing,
+                        &[pivot, a, b],
+                        &digits(pivot_mask | z).collect::<Vec<_>>(),
+                        eliminations,
+                    ) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // Chains of conjugate pairs (the only 2 places for a digit in a unit) alternate between true & false, so are coloured in 2 colours.
+    // * Colour wrap: if 2 cells of the same colour see each other, that colour is false.
+    // * Colour trap: a cell which sees both colours cannot hold the digit.
+    fn find_simple_coloring(&self) -> Option<Step> {
+        for digit in 0..9 {
+            let mut links: Vec<Vec<usize>> = vec![Vec::new(); 9 * 9];
+
+            for unit in 0..self.units().units.len() {
+                let positions = self.positions(unit, digit);
+
+                if positions.len() == 2 {
+                    links[positions[0]].push(positions[1]);
+                    links[positions[1]].push(positions[0]);
+                }
+            }
+
+            let mut colors: [Option<bool>; 9 * 9] = [None; 9 * 9];
+
+            for start in 0..9 * 9 {
+                if colors[start].is_some() || links[start].is_empty() {
+                    continue;
+                }
+
+                let mut chain: Vec<usize> = Vec::new();
+                let mut queue = VecDeque::from(vec![start]);
+                colors[start] = Some(true);
+
+                while let Some(index) = queue.pop_front() {
+                    chain.push(index);
+                    let color = colors[index].expect("must be coloured");
+
+                    for next in links[index].iter().copied() {
+                        if colors[next].is_none() {
+                            colors[next] = Some(!color);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+
+                for color in [true, false] {
+                    let same: Vec<usize> = chain
+                        .iter()
+                        .copied()
+                        .filter(|index| colors[*index] == Some(color))
+                        .collect();
+
+                    if same
+                        .iter()
+                        .any(|a| same.iter().any(|b| self.units().peers[*a].contains(b)))
+                    {
+                        let eliminations = same.iter().map(|index| (*index, digit)).collect();
+                        return elimination(
+                            Technique::SimpleColoring,
+                            &chain,
+                            &[digit],
+                            eliminations,
+                        );
+                    }
+                }
+
+                let eliminations = (0..9 * 9)
+                    .filter(|index| self.mask(*index) & (1 << digit) != 0 && !chain.contains(index))
+                    .filter(|index| {
+                        let sees = |color: bool| {
+                            chain.iter().any(|c| {
+                                colors[*c] == Some(color) && self.units().peers[*index].contains(c)
+                            })
+                        };
+                        sees(true) && sees(false)
+                    })
+                    .map(|index| (index, digit))
+                    .collect();
+
+                if let Some(step) =
+                    elimination(Technique::SimpleColoring, &chain, &[digit], eliminations)
+                {
+                    return Some(step);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Iterator for Logical {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.problem.is_valid() || self.problem.is_complete() {
+            return None;
+        }
+
+        let step = Technique::all()
+            .iter()
+            .find_map(|technique| self.find_step(*technique))?;
+        self.apply(&step);
+        Some(step)
+    }
+}
+
+fn as_cell(index: usize) -> Cell {
+    Cell::index(index).expect("must be a valid index")
+}
+
+fn as_value(digit: usize) -> SudokuValue {
+    SudokuValue::candidates()[digit]
+}
+
+fn digits(mask: u16) -> impl Iterator<Item = usize> {
+    (0..9).filter(move |digit| mask & (1 << digit) != 0)
+}
+
+fn placement(technique: Technique, index: usize, digit: usize) -> Step {
+    Step {
+        technique,
+        cells: vec![as_cell(index)],
+        values: vec![as_value(digit)],
+        eliminations: Vec::default(),
+        placement: Some((as_cell(index), as_value(digit))),
+    }
+}
+
+// A step which eliminates candidates, or `None` if there is nothing to eliminate.
+fn elimination(
+    technique: Technique,
+    cells: &[usize],
+    digits: &[usize],
+    eliminations: Vec<(usize, usize)>,
+) -> Option<Step> {
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    Some(Step {
+        technique,
+        cells: cells.iter().map(|index| as_cell(*index)).collect(),
+        values: digits.iter().map(|digit| as_value(*digit)).collect(),
+        eliminations: eliminations
+            .into_iter()
+            .map(|(index, digit)| (as_cell(index), as_value(digit)))
+            .collect(),
+        placement: None,
+    })
+}
+
+fn combinations(items: &[usize], size: usize) -> Vec<Vec<usize>> {
+    if size == 0 {
+        return vec![Vec::default()];
+    }
+
+    let mut result = Vec::default();
+
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, *item);
+            result.push(rest);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::format::Format;
+    use crate::model::jigsaw::Jigsaw;
+    use crate::model::variant::Variant;
+    use crate::solver::dlx::Dlx;
+    use crate::solver::Solver;
+    use std::str::FromStr;
+
+    // A blank problem where only the pencil marks are set up (via `unmark`).
+    fn blank() -> Logical {
+        Logical::new(SudokuProblem::default())
+    }
+
+    fn unmark(logical: &mut Logical, cells: &[(usize, usize)], values: &[SudokuValue]) {
+        for (row, column) in cells {
+            for value in values {
+                logical
+                    .pencil_marks
+                    .remove(&Cell::row_column(*row, *column).unwrap(), *value);
+            }
+        }
+    }
+
+    fn cell(row: usize, column: usize) -> Cell {
+        Cell::row_column(row, column).unwrap()
+    }
+
+    fn all_except(value: SudokuValue) -> Vec<SudokuValue> {
+        SudokuValue::candidates()
+            .iter()
+            .copied()
+            .filter(|v| *v != value)
+            .collect()
+    }
+
+    #[test]
+    fn singles_only() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+
+        // Execute
+        let path = Logical::new(problem).run();
+
+        // Verify
+        assert!(path.is_solved());
+        assert_eq!(
+            path.result(),
+            &SudokuProblem::from_str(
+                "379526814564318972281479365435267198698143527712895436923754681146982753857631249"
+            )
+            .unwrap()
+        );
+        assert!(path
+            .steps()
+            .iter()
+            .all(|step| step.technique <= Technique::HiddenSingle && step.placement.is_some()));
+    }
+
+    #[test]
+    fn steps_are_sound() {
+        for sdm in [
+            "016400000200009000400000062070230100100000003003087040960000005000800007000006820",
+            "000605000003020800045090270500000001062000540400000007098060450006040700000203000",
+            "000093006000800900020006100000080053006000200370050000002500040001009000700130000",
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        ] {
+            // Setup
+            let problem = SudokuProblem::from_str(sdm).unwrap();
+            let solution = Dlx::new(problem.clone()).run().unwrap();
+
+            // Execute
+            let path = Logical::new(problem).run();
+
+            // Verify
+            for step in path.steps() {
+                if let Some((cell, value)) = step.placement {
+                    assert_eq!(solution.value(&cell), value, "{}", step);
+                }
+
+                for (cell, value) in step.eliminations.iter() {
+                    assert_ne!(solution.value(cell), *value, "{}", step);
+                }
+            }
+
+            if path.is_solved() {
+                assert_eq!(path.result(), &solution);
+            }
+        }
+    }
+
+    #[test]
+    fn variants() {
+        for (variant, sdm) in [
+            (
+                Variant::X,
+                "001739000500000600000050000040081090008090300020670040000060000006000008000417900",
+            ),
+            (
+                Variant::Hyper,
+                "800000109400003000031000406000000600610000054005000000907000840000100002103000005",
+            ),
+        ] {
+            // Setup
+            let problem = SudokuProblem::from_str(sdm)
+                .unwrap()
+                .with_variant(variant)
+                .unwrap();
+            let solution = Dlx::new(problem.clone()).run().unwrap();
+
+            // Execute
+            let path = Logical::new(problem).run();
+
+            // Verify
+            assert!(path.is_solved(), "{}", variant);
+            assert_eq!(path.result(), &solution, "{}", variant);
+        }
+    }
+
+    #[test]
+    fn jigsaw() {
+        // Setup
+        let jigsaw = Jigsaw::from_str(
+            &[
+                "aaabbbccc",
+                "aabbbcccc",
+                "daaabeecc",
+                "ddabbefff",
+                "dddeeefff",
+                "dggehhfff",
+                "ddgeehiii",
+                "ggghhhiii",
+                "ggghhhiii",
+            ]
+            .concat(),
+        )
+        .unwrap();
+        let layout = SudokuProblem::default().with_jigsaw(&jigsaw).unwrap();
+        let problem = Format::Sdm
+            .parse_with(
+                "060000850785004006830000010304070209270900000498000075007002500620030008000798000",
+                &layout,
+            )
+            .unwrap();
+
+        // Execute
+        let path = Logical::new(problem.clone()).run();
+
+        // Verify
+        assert!(path.is_solved());
+        assert_eq!(path.result(), &Dlx::new(problem).run().unwrap());
+    }
+
+    #[test]
+    fn unsolved() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        )
+        .unwrap();
+
+        // Execute
+        let path = Logical::new(problem.clone()).run();
+
+        // Verify
+        assert!(!path.is_solved());
+        assert!(!path.result().is_complete());
+        assert_eq!(Logical::new(SudokuProblem::default()).run().steps(), &[]);
+    }
+
+    #[test]
+    fn naked_single() {
+        let mut logical = blank();
+        unmark(&mut logical, &[(0, 0)], &all_except(SudokuValue::Five));
+
+        let step = logical.find_step(Technique::NakedSingle).unwrap();
+        assert_eq!(step.placement, Some((cell(0, 0), SudokuValue::Five)));
+        assert_eq!(step.to_string(), "Naked Single: r1c1 = 5");
+
+        logical.apply(&step);
+        assert_eq!(logical.problem().value(&cell(0, 0)), SudokuValue::Five);
+        assert!(!logical
+            .pencil_marks()
+            .contains(&cell(0, 8), SudokuValue::Five));
+        assert!(!logical
+            .pencil_marks()
+            .contains(&cell(8, 0), SudokuValue::Five));
+        assert!(!logical
+            .pencil_marks()
+            .contains(&cell(2, 2), SudokuValue::Five));
+        assert!(logical
+            .pencil_marks()
+            .contains(&cell(3, 3), SudokuValue::Five));
+    }
+
+    #[test]
+    fn hidden_single() {
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = (0..9).filter(|c| *c != 4).map(|c| (0, c)).collect();
+        unmark(&mut logical, &others, &[SudokuValue::Three]);
+
+        let step = logical.find_step(Technique::HiddenSingle).unwrap();
+        assert_eq!(step.placement, Some((cell(0, 4), SudokuValue::Three)));
+    }
+
+    #[test]
+    fn naked_pair() {
+        let mut logical = blank();
+        let pair = [(0, 0), (0, 1)];
+        let mut others = all_except(SudokuValue::One);
+        others.retain(|v| *v != SudokuValue::Two);
+        unmark(&mut logical, &pair, &others);
+
+        let step = logical.find_step(Technique::NakedPair).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(0, 1)]);
+        assert_eq!(step.values, vec![SudokuValue::One, SudokuValue::Two]);
+        assert_eq!(step.eliminations.len(), 14);
+        assert!(step.eliminations.contains(&(cell(0, 8), SudokuValue::Two)));
+        assert_eq!(step.placement, None);
+    }
+
+    #[test]
+    fn hidden_pair() {
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = (2..9).map(|c| (0, c)).collect();
+        unmark(&mut logical, &others, &[SudokuValue::One, SudokuValue::Two]);
+        assert_eq!(logical.find_step(Technique::NakedPair), None);
+
+        let step = logical.find_step(Technique::HiddenPair).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(0, 1)]);
+        assert_eq!(step.values, vec![SudokuValue::One, SudokuValue::Two]);
+        assert_eq!(step.eliminations.len(), 14);
+        assert!(step.eliminations.contains(&(cell(0, 0), SudokuValue::Nine)));
+    }
+
+    #[test]
+    fn naked_triple() {
+        let mut logical = blank();
+        let triple = [(0, 0), (4, 0), (8, 0)];
+        let keep = [SudokuValue::One, SudokuValue::Two, SudokuValue::Three];
+        let others: Vec<SudokuValue> = SudokuValue::candidates()
+            .iter()
+            .copied()
+            .filter(|v| !keep.contains(v))
+            .collect();
+        unmark(&mut logical, &triple, &others);
+        // Make it a {12}, {23}, {13} triple.
+        unmark(&mut logical, &[(0, 0)], &[SudokuValue::Three]);
+        unmark(&mut logical, &[(4, 0)], &[SudokuValue::One]);
+        unmark(&mut logical, &[(8, 0)], &[SudokuValue::Two]);
+        assert_eq!(logical.find_step(Technique::NakedPair), None);
+
+        let step = logical.find_step(Technique::NakedTriple).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(4, 0), cell(8, 0)]);
+        assert_eq!(step.values, keep.to_vec());
+        // The other 6 cells of the column.
+        assert_eq!(step.eliminations.len(), 6 * 3);
+    }
+
+    #[test]
+    fn hidden_triple() {
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = (3..9).map(|r| (r, 4)).collect();
+        unmark(
+            &mut logical,
+            &others,
+            &[SudokuValue::Four, SudokuValue::Five, SudokuValue::Six],
+        );
+        assert_eq!(logical.find_step(Technique::HiddenPair), None);
+
+        let step = logical.find_step(Technique::HiddenTriple).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 4), cell(1, 4), cell(2, 4)]);
+        assert_eq!(
+            step.values,
+            vec![SudokuValue::Four, SudokuValue::Five, SudokuValue::Six]
+        );
+        assert_eq!(step.eliminations.len(), 3 * 6);
+    }
+
+    #[test]
+    fn pointing_pair() {
+        let mut logical = blank();
+        unmark(
+            &mut logical,
+            &[(1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)],
+            &[SudokuValue::Five],
+        );
+
+        let step = logical.find_step(Technique::PointingPair).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(0, 1), cell(0, 2)]);
+        assert_eq!(
+            step.eliminations,
+            (3..9)
+                .map(|c| (cell(0, c), SudokuValue::Five))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn box_line_reduction() {
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = (3..9).map(|c| (0, c)).collect();
+        unmark(&mut logical, &others, &[SudokuValue::Five]);
+
+        let step = logical.find_step(Technique::BoxLineReduction).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(0, 1), cell(0, 2)]);
+        assert_eq!(
+            step.eliminations,
+            vec![
+                (cell(1, 0), SudokuValue::Five),
+                (cell(1, 1), SudokuValue::Five),
+                (cell(1, 2), SudokuValue::Five),
+                (cell(2, 0), SudokuValue::Five),
+                (cell(2, 1), SudokuValue::Five),
+                (cell(2, 2), SudokuValue::Five),
+            ]
+        );
+    }
+
+    #[test]
+    fn x_wing() {
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = [0, 4]
+            .iter()
+            .flat_map(|r| (0..9).filter(|c| *c != 2 && *c != 6).map(move |c| (*r, c)))
+            .collect();
+        unmark(&mut logical, &others, &[SudokuValue::One]);
+
+        let step = logical.find_step(Technique::XWing).unwrap();
+        assert_eq!(
+            step.cells,
+            vec![cell(0, 2), cell(0, 6), cell(4, 2), cell(4, 6)]
+        );
+        assert_eq!(step.values, vec![SudokuValue::One]);
+        assert_eq!(step.eliminations.len(), 2 * 7);
+        assert!(step.eliminations.contains(&(cell(8, 6), SudokuValue::One)));
+        assert!(!step.eliminations.contains(&(cell(4, 6), SudokuValue::One)));
+    }
+
+    #[test]
+    fn swordfish() {
+        let mut logical = blank();
+        let rows: [(usize, [usize; 2]); 3] = [(0, [0, 4]), (3, [4, 8]), (6, [0, 8])];
+
+        for (row, columns) in rows.iter() {
+            let others: Vec<(usize, usize)> = (0..9)
+                .filter(|c| !columns.contains(c))
+                .map(|c| (*row, c))
+                .collect();
+            unmark(&mut logical, &others, &[SudokuValue::One]);
+        }
+
+        assert_eq!(logical.find_step(Technique::XWing), None);
+        let step = logical.find_step(Technique::Swordfish).unwrap();
+        assert_eq!(step.cells.len(), 6);
+        assert_eq!(step.eliminations.len(), 3 * 6);
+        assert!(step.eliminations.contains(&(cell(1, 0), SudokuValue::One)));
+        assert!(!step.eliminations.contains(&(cell(1, 1), SudokuValue::One)));
+    }
+
+    #[test]
+    fn xy_wing() {
+        let mut logical = blank();
+        let pattern = [
+            ((4, 4), [SudokuValue::One, SudokuValue::Two]),
+            ((4, 0), [SudokuValue::One, SudokuValue::Three]),
+            ((0, 4), [SudokuValue::Two, SudokuValue::Three]),
+        ];
+
+        for ((row, column), keep) in pattern.iter() {
+            let others: Vec<SudokuValue> = SudokuValue::candidates()
+                .iter()
+                .copied()
+                .filter(|v| !keep.contains(v))
+                .collect();
+            unmark(&mut logical, &[(*row, *column)], &others);
+        }
+
+        let step = logical.find_step(Technique::XYWing).unwrap();
+        assert_eq!(step.cells, vec![cell(4, 4), cell(0, 4), cell(4, 0)]);
+        assert_eq!(
+            step.values,
+            vec![SudokuValue::One, SudokuValue::Two, SudokuValue::Three]
+        );
+        assert_eq!(step.eliminations, vec![(cell(0, 0), SudokuValue::Three)]);
+    }
+
+    #[test]
+    fn simple_coloring_wrap() {
+        // r1c1 - r1c2 (row 1) & r1c1 - r2c1 (column 1): r1c2 & r2c1 share a colour & box 1.
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = (2..9)
+            .map(|c| (0, c))
+            .chain((2..9).map(|r| (r, 0)))
+            .collect();
+        unmark(&mut logical, &others, &[SudokuValue::Seven]);
+
+        let step = logical.find_step(Technique::SimpleColoring).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(0, 1), cell(1, 0)]);
+        assert_eq!(
+            step.eliminations,
+            vec![
+                (cell(0, 1), SudokuValue::Seven),
+                (cell(1, 0), SudokuValue::Seven)
+            ]
+        );
+    }
+
+    #[test]
+    fn simple_coloring_trap() {
+        // r1c1 - r1c9 (row 1) & r1c9 - r2c9 (column 9): the rest of box 3 sees both colours.
+        let mut logical = blank();
+        let others: Vec<(usize, usize)> = (1..8)
+            .map(|c| (0, c))
+            .chain((2..9).map(|r| (r, 8)))
+            .collect();
+        unmark(&mut logical, &others, &[SudokuValue::Seven]);
+
+        let step = logical.find_step(Technique::SimpleColoring).unwrap();
+        assert_eq!(step.cells, vec![cell(0, 0), cell(0, 8), cell(1, 8)]);
+        assert_eq!(
+            step.eliminations,
+            vec![
+                (cell(1, 6), SudokuValue::Seven),
+                (cell(1, 7), SudokuValue::Seven),
+                (cell(2, 6), SudokuValue::Seven),
+                (cell(2, 7), SudokuValue::Seven),
+            ]
+        );
+    }
+
+    #[test]
+    fn display_step() {
+        let step = Step {
+            technique: Technique::NakedPair,
+            cells: vec![cell(0, 0), cell(0, 1)],
+            values: vec![SudokuValue::One, SudokuValue::Two],
+            eliminations: vec![(cell(0, 5), SudokuValue::Two)],
+            placement: None,
+        };
+        assert_eq!(
+            step.to_string(),
+            "Naked Pair {1,2} at r1c1, r1c2: r1c6 <> 2"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::model::board::SudokuProblem;
+use crate::model::cell::{Cell, SudokuValue};
+use crate::model::unit::Units;
+use crate::solver::search::SearchError;
+use crate::solver::Solver;
+
+const ALL_CANDIDATES: u16 = 0b1_1111_1111;
+
+#[derive(Debug)]
+pub struct Propagation {
+    problem: SudokuProblem,
+}
+
+impl Propagation {
+    pub fn new(problem: SudokuProblem) -> Propagation {
+        Self { problem }
+    }
+}
+
+impl Solver for Propagation {
+    fn solutions(&self, limit: usize) -> Result<Vec<SudokuProblem>, SearchError> {
+        let mut solutions = Vec::new();
+        let mut stack: Vec<Candidates> = Candidates::from_problem(&self.problem)
+            .into_iter()
+            .collect();
+
+        while solutions.len() < limit {
+            let current = match stack.pop() {
+                Some(current) => current,
+                None => break,
+            };
+
+            match current.branch_index() {
+                Some(index) => {
+                    for digit in current.digits(index) {
+                        let mut candidates = current.clone();
+
+                        if candidates.assign(index, digit).is_ok() {
+                            stack.push(candidates);
+                        }
+                    }
+                }
+                None => {
+                    solutions.push(current.to_problem(&self.problem));
+                }
+            }
+        }
+
+        Ok(solutions)
+    }
+}
+
+// The remaining candidate digits (as a bitmask, bit `d` for the digit `d + 1`) of each cell.
+// Every mutation propagates to a fixpoint:
+// * naked single - when a cell is down to one candidate, that digit is eliminated from all its peers (and the digits either side from its neighbours).
+// * hidden single - when a digit has only one place left in a unit, it is assigned there.
+// An `Err` means the candidates have reached a contradiction.
+#[derive(Clone)]
+struct Candidates<'a> {
+    masks: [u16; 9 * 9],
+    units: &'a Units,
+}
+
+impl<'a> Candidates<'a> {
+    fn from_problem(problem: &'a SudokuProblem) -> Result<Self, ()> {
+        let mut candidates = Self {
+            masks: [ALL_CANDIDATES; 9 * 9],
+            units: problem.units(),
+        };
+
+        for index in 0..9 * 9 {
+            let cell = Cell::index(index).expect("must be a valid index");
+
+            if let Some(digit) = problem.value(&cell).to_count_index() {
+                candidates.assign(index, digit)?;
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    fn to_problem(&self, problem: &SudokuProblem) -> SudokuProblem {
+        let mut solution = problem.clone();
+
+        for (index, mask) in self.masks.iter().enumerate() {
+            let cell = Cell::index(index).expect("must be a valid index");
+            let value = SudokuValue::candidates()[mask.trailing_zeros() as usize];
+            solution = solution.replace(&cell, value);
+        }
+
+        solution
+    }
+
+    fn digits(&self, index: usize) -> impl Iterator<Item = usize> {
+        let mask = self.masks[index];
+        (0..9).filter(move |digit| mask & (1 << digit) != 0)
+    }
+
+    // The undecided cell with the fewest candidates, or `None` when every cell is decided.
+    fn branch_index(&self) -> Option<usize> {
+        self.masks
+            .iter()
+            .enumerate()
+            .filter(|(_, mask)| mask.count_ones() > 1)
+            .min_by_key(|(_, mask)| mask.count_ones())
+            .map(|(index, _)| index)
+    }
+
+    fn assign(&mut self, index: usize, digit: usize) -> Result<(), ()> {
+        let others = self.masks[index] & !(1 << digit);
+
+        for other in 0..9 {
+            if others & (1 << other) != 0 {
+                self.eliminate(index, other)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn eliminate(&mut self, index: usize, digit: usize) -> Result<(), ()> {
+        let bit = 1 << digit;
+
+        if self.masks[index] & bit == 0 {
+            return Ok(());
+        }
+
+        self.masks[index] &= !bit;
+
+        match self.masks[index].count_ones() {
+            0 => return Err(()),
+            1 => {
+                let remaining = self.masks[index].trailing_zeros() as usize;
+
+                for peer in self.units.peers[index].iter() {
+                    self.eliminate(*peer, remaining)?;
+                }
+
+                for neighbour in self.units.neighbours[index].iter() {
+                    if remaining > 0 {
+                        self.eliminate(*neighbour, remaining - 1)?;
+                    }
+
+                    if remaining < 8 {
+                        self.eliminate(*neighbour, remaining + 1)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for unit in self.units.cell_units[index].iter() {
+            let mut places = self.units.units[*unit]
+                .iter()
+                .filter(|place| self.masks[**place] & bit != 0);
+
+            match (places.next(), places.next()) {
+                (None, _) => return Err(()),
+                (Some(place), None) => self.assign(*place, digit)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::search::Search;
+    use std::str::FromStr;
+
+    #[test]
+    fn propagation() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let propagation = Propagation::new(problem);
+
+        // Execute
+        let result = propagation.run().unwrap();
+
+        // Verify
+        assert_eq!(
+            result,
+            SudokuProblem::from_str(
+                "379526814564318972281479365435267198698143527712895436923754681146982753857631249"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn propagation_hard() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        )
+        .unwrap();
+        let propagation = Propagation::new(problem);
+
+        // Execute
+        let result = propagation.run().unwrap();
+
+        // Verify
+        assert_eq!(
+            result,
+            SudokuProblem::from_str(
+                "812753649943682175675491283154237896369845721287169534521974368438526917796318452"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn propagation_matches_search() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "016400000200009000400000062070230100100000003003087040960000005000800007000006820",
+        )
+        .unwrap();
+
+        // Execute
+        let propagation = Propagation::new(problem.clone()).run().unwrap();
+        let search = Search::new(problem).run().unwrap();
+
+        // Verify
+        assert_eq!(propagation, search);
+    }
+
+    #[test]
+    fn propagation_unsolvable() {
+        // Duplicate values.
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap()
+        .replace(&Cell::row_column(0, 3).unwrap(), SudokuValue::Three);
+        assert_matches!(
+            Propagation::new(problem).run(),
+            Err(SearchError::NoSolution)
+        );
+
+        // The first cell has no candidates: 1-8 are in its row, 9 is in its column.
+        let problem = SudokuProblem::from_str(
+            "012345678900000000000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        assert_matches!(
+            Propagation::new(problem).run(),
+            Err(SearchError::NoSolution)
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crate::model::cell::SudokuValue;
+use crate::model::puzzle::Puzzle;
+use crate::model::samurai::{position, SamuraiProblem, SIDE};
+use crate::model::unit::UNITS;
+use crate::solver::search::{Branching, Limits, Search, SearchError, SearchReport, TieBreak};
+
+// Solves a samurai puzzle by a depth first search on the cells with the fewest candidates.
+// After each placement, the naked & hidden singles of every grid are placed until none are left.
+// Since a shared box is in two grids, each of its singles is placed into both, and so propagates on from one grid to the other.
+#[derive(Debug)]
+pub struct Samurai {
+    problem: SamuraiProblem,
+    limits: Limits,
+}
+
+impl Samurai {
+    pub fn new(problem: SamuraiProblem) -> Samurai {
+        Self {
+            problem,
+            limits: Limits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Samurai {
+        self.limits = limits;
+        self
+    }
+
+    pub fn run(&self) -> Result<SamuraiProblem, SearchError> {
+        self.search(1)?
+            .solutions
+            .pop()
+            .ok_or(SearchError::NoSolution)
+    }
+
+    // Find up to `limit` distinct solutions.
+    pub fn search(&self, limit: usize) -> Result<SearchReport<SamuraiProblem>, SearchError> {
+        let report = Search::new(Propagated::new(self.problem.clone()))
+            .with_limits(self.limits.clone())
+            .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+            .search(limit, &mut ())?;
+
+        Ok(SearchReport {
+            solutions: report
+                .solutions
+                .into_iter()
+                .map(|propagated| propagated.problem)
+                .collect(),
+            stats: report.stats,
+        })
+    }
+}
+
+// A samurai problem whose singles have all been placed, or else which has run into a contradiction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Propagated {
+    problem: SamuraiProblem,
+    valid: bool,
+}
+
+impl Propagated {
+    fn new(mut problem: SamuraiProblem) -> Propagated {
+        let valid = propagate(&mut problem);
+        Self { problem, valid }
+    }
+}
+
+impl Puzzle for Propagated {
+    fn cell_count(&self) -> usize {
+        self.problem.cell_count()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn is_complete(&self) -> bool {
+        self.problem.is_complete()
+    }
+
+    fn is_unknown(&self, index: usize) -> bool {
+        self.problem.is_unknown(index)
+    }
+
+    fn candidate_mask(&self, index: usize) -> u32 {
+        self.problem.candidate_mask(index)
+    }
+
+    fn peers(&self, index: usize) -> &[usize] {
+        self.problem.peers(index)
+    }
+
+    fn place_digit(&self, index: usize, digit: usize) -> Self {
+        Propagated::new(self.problem.place_digit(index, digit))
+    }
+}
+
+// Place the naked & hidden singles of every grid until none are left.
+// Returns false on a contradiction: a cell without candidates, or a digit with nowhere to go in a unit.
+fn propagate(problem: &mut SamuraiProblem) -> bool {
+    loop {
+        if !problem.is_valid() {
+            return false;
+        }
+
+        let mut progress = false;
+
+        for index in 0..SIDE * SIDE {
+            if !problem.is_unknown(index) {
+                continue;
+            }
+
+            match problem.candidates(index) {
+                0 => return false,
+                mask if mask.count_ones() == 1 => {
+                    problem.place(index, as_value(mask));
+                    progress = true;
+                }
+                _ => {}
+            }
+        }
+
+        for grid in 0..problem.grids().len() {
+            for unit in UNITS.iter() {
+                for digit in 0..9 {
+                    let bit = 1 << digit;
+                    let mut places = unit
+                        .iter()
+                        .map(|index| position(grid, *index))
+                        .filter(|index| problem.candidates(*index) & bit != 0);
+
+                    match (places.next(), places.next()) {
+                        (None, _) => return false,
+                        (Some(index), None) if problem.is_unknown(index) => {
+                            problem.place(index, as_value(bit));
+                            progress = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if !progress {
+            return problem.is_valid();
+        }
+    }
+}
+
+fn as_value(mask: u16) -> SudokuValue {
+    SudokuValue::candidates()[mask.trailing_zeros() as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn samurai() {
+        // Setup
+        let problem = SamuraiProblem::from_str(SAMURAI).unwrap();
+
+        // Execute
+        let report = Samurai::new(problem).search(2).unwrap();
+
+        // Verify
+        assert_eq!(report.solutions.len(), 1);
+        assert_eq!(report.solutions[0].to_string(), SOLUTION);
+        assert!(report.solutions[0].is_complete());
+        // Each of the singles propagates through the shared boxes, so the search never has to guess.
+        assert_eq!(report.stats.dead_ends, 0);
+    }
+
+    #[test]
+    fn samurai_multiple() {
+        let report = Samurai::new(SamuraiProblem::default()).search(2).unwrap();
+        assert_eq!(report.solutions.len(), 2);
+        assert!(report.solutions.iter().all(SamuraiProblem::is_complete));
+    }
+
+    #[test]
+    fn samurai_unsolvable() {
+        // Setup
+        let mut rows = SAMURAI
+            .lines()
+            .map(|row| row.replace(|c: char| c.is_ascii_digit(), "."))
+            .collect::<Vec<_>>();
+        // The top left cell of the centre grid can only be a 7, 8 or 9 in its row of the top left grid,
+        // but none of them in its column of the centre grid.
+        rows[6] = format!("123456{}", ".".repeat(15));
+        rows[9] = "      7........".to_string();
+        rows[10] = "      8........".to_string();
+        rows[11] = "      9........".to_string();
+        let problem = SamuraiProblem::from_str(&rows.join("\n")).unwrap();
+
+        // Execute
+        let solution = Samurai::new(problem).run();
+
+        // Verify
+        assert_eq!(solution, Err(SearchError::NoSolution));
+    }
+
+    const SAMURAI: &str = "\
+...6.4..1   7....43..
+.5.3..9.7   ..85..97.
+.2.98..5.   126..3854
+.7....2..   ..73..1..
+..5....46   .8...2.6.
+41.5.....   ..46.7...
+7.3...1.2.8...3.89...
+.....3.79....62..5..3
+.....................
+      9...5..1.
+      .5...8.94
+      ..17.4.8.
+..4......4...2.......
+5....7...21.....3.9.2
+...6....78.....65....
+...27..3.   9.....31.
+6.......2   673...4..
+.1..9.7..   .4.87....
+.987..1..   .6.5...3.
+3.2.4....   59....7..
+...53...9   .14.....9";
+    const SOLUTION: &str = "\
+987654321   759864321
+654321987   438521976
+321987654   126973854
+879436215   697358142
+235198746   385142769
+416572893   214697538
+793845162987543289617
+568213479531862715493
+142769538642971436285
+      984356217
+      753128694
+      621794385
+764321895473126987543
+521987346219758431962
+983654217865439652871
+849276531   985246317
+637415982   673195428
+215893764   241873695
+498762153   867529134
+352149678   592314786
+176538429   314768259";
+}
@@ -1,49 +1,351 @@
 use crate::model::board::SudokuProblem;
-use crate::model::cell::SudokuValue;
+use crate::model::puzzle::Puzzle;
+use crate::solver::Solver;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+// Cancels a search from another thread; clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// The budgets of a search, which are unlimited by default.
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    pub time: Option<Duration>,
+    pub nodes: Option<usize>,
+    pub cancellation: Option<CancellationToken>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchError {
+    NoSolution,
+    TimeLimit,
+    NodeLimit,
+    Cancelled,
+}
+
+impl std::fmt::Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchError::NoSolution => write!(f, "no solution"),
+            SearchError::TimeLimit => write!(f, "time limit exceeded"),
+            SearchError::NodeLimit => write!(f, "node limit exceeded"),
+            SearchError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+// Receives the events of a search, for example to visualize it or compare heuristics.
+// The `depth` of a node is the number of values the search placed to reach it.
+pub trait Observer<P = SudokuProblem> {
+    fn push(&mut self, _problem: &P, _depth: usize) {}
+
+    fn pop(&mut self, _problem: &P, _depth: usize) {}
+
+    fn solution(&mut self, _problem: &P) {}
+}
+
+impl<P> Observer<P> for () {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    // The nodes popped off of the stack.
+    pub nodes: usize,
+    // The nodes which have neither a solution nor any valid child.
+    pub dead_ends: usize,
+    // The most nodes waiting on the stack at once.
+    pub max_depth: usize,
+    pub elapsed: Duration,
+}
+
+impl std::fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} dead ends, max depth {} in {:.3}ms",
+            self.nodes,
+            self.dead_ends,
+            self.max_depth,
+            self.elapsed.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchReport<P = SudokuProblem> {
+    pub solutions: Vec<P>,
+    pub stats: SearchStats,
+}
+
+const BRANCHINGS: [Branching; 4] = [
+    Branching::FirstUnknown,
+    Branching::MinimumRemainingValues(TieBreak::First),
+    Branching::MinimumRemainingValues(TieBreak::Last),
+    Branching::MinimumRemainingValues(TieBreak::MostUnknownPeers),
+];
+
+// How the search picks the cell to branch on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Branching {
+    #[default]
+    FirstUnknown,
+    // The unknown cell with the fewest candidates.
+    MinimumRemainingValues(TieBreak),
+}
+
+// How to choose between the unknown cells with equally few candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    First,
+    Last,
+    // The cell which constrains the most other unknown cells.
+    MostUnknownPeers,
+}
+
+impl Branching {
+    pub fn all() -> &'static [Branching] {
+        &BRANCHINGS[..]
+    }
+
+    // The index of the cell to branch on, or `None` when every cell is known.
+    fn choose<P: Puzzle>(&self, problem: &P) -> Option<usize> {
+        let tie_break = match self {
+            Branching::FirstUnknown => {
+                return (0..problem.cell_count()).find(|index| problem.is_unknown(*index))
+            }
+            Branching::MinimumRemainingValues(tie_break) => tie_break,
+        };
+        let mut best: Option<(usize, u32, usize)> = None;
+
+        for index in 0..problem.cell_count() {
+            if !problem.is_unknown(index) {
+                continue;
+            }
+
+            let remaining = problem.candidate_mask(index).count_ones();
+
+            // Without candidates, the cell is a dead end.
+            if remaining == 0 {
+                return Some(index);
+            }
+
+            let unknown_peers = match tie_break {
+                TieBreak::MostUnknownPeers => problem
+                    .peers(index)
+                    .iter()
+                    .filter(|peer| problem.is_unknown(**peer))
+                    .count(),
+                _ => 0,
+            };
+            let better = match best {
+                None => true,
+                Some((_, best_remaining, best_unknown_peers)) => match tie_break {
+                    TieBreak::First => remaining < best_remaining,
+                    TieBreak::Last => remaining <= best_remaining,
+                    TieBreak::MostUnknownPeers => {
+                        remaining < best_remaining
+                            || (remaining == best_remaining && unknown_peers > best_unknown_peers)
+                    }
+                },
+            };
+
+            if better {
+                best = Some((index, remaining, unknown_peers));
+            }
+        }
+
+        best.map(|(index, _, _)| index)
+    }
+}
+
+impl std::fmt::Display for Branching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Branching::FirstUnknown => write!(f, "first-unknown"),
+            Branching::MinimumRemainingValues(TieBreak::First) => write!(f, "mrv-first"),
+            Branching::MinimumRemainingValues(TieBreak::Last) => write!(f, "mrv-last"),
+            Branching::MinimumRemainingValues(TieBreak::MostUnknownPeers) => {
+                write!(f, "mrv-most-unknown-peers")
+            }
+        }
+    }
+}
+
+// Generic over the size of the grid (see `model::sized`), although only the 9x9 `SudokuProblem` is a `Solver`.
 #[derive(Debug)]
-pub struct Search {
-    problem: SudokuProblem,
+pub struct Search<P = SudokuProblem> {
+    problem: P,
+    limits: Limits,
+    branching: Branching,
 }
 
-impl Search {
-    pub fn new(problem: SudokuProblem) -> Search {
-        Self { problem }
+impl<P: Puzzle> Search<P> {
+    pub fn new(problem: P) -> Search<P> {
+        Self {
+            problem,
+            limits: Limits::default(),
+            branching: Branching::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Search<P> {
+        self.limits = limits;
+        self
     }
 
-    pub fn run(&self) -> Result<SudokuProblem, ()> {
-        let mut stack = vec![self.problem.clone()];
+    pub fn with_branching(mut self, branching: Branching) -> Search<P> {
+        self.branching = branching;
+        self
+    }
 
-        while !stack.is_empty() {
-            let current = stack.pop().expect("stack must not be empty");
+    pub fn run(&self) -> Result<P, SearchError> {
+        self.search(1, &mut ())?
+            .solutions
+            .pop()
+            .ok_or(SearchError::NoSolution)
+    }
 
-            match current.position(&SudokuValue::Unknown) {
-                Some(cell) => {
-                    for candidate in SudokuValue::candidates() {
-                        let problem = current.replace(&cell, *candidate);
+    // Find up to `limit` distinct solutions, reporting each event to the `observer`.
+    // Fails (never with `NoSolution`) when a budget runs out before the search is finished.
+    pub fn search(
+        &self,
+        limit: usize,
+        observer: &mut impl Observer<P>,
+    ) -> Result<SearchReport<P>, SearchError> {
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let mut solutions = Vec::new();
+        let mut stack = vec![(self.problem.clone(), 0)];
+        stats.max_depth = stack.len();
+        observer.push(&self.problem, 0);
+
+        while solutions.len() < limit {
+            let (current, depth) = match stack.pop() {
+                Some(entry) => entry,
+                None => break,
+            };
+            self.check(start, stats.nodes)?;
+            observer.pop(&current, depth);
+            stats.nodes += 1;
+
+            match self.branching.choose(&current) {
+                Some(index) => {
+                    let before = stack.len();
+                    let mask = current.candidate_mask(index);
+
+                    for digit in (0..u32::BITS as usize).filter(|digit| mask & (1 << digit) != 0) {
+                        let problem = current.place_digit(index, digit);
 
                         if problem.is_valid() {
-                            stack.push(problem);
+                            observer.push(&problem, depth + 1);
+                            stack.push((problem, depth + 1));
                         }
                     }
+
+                    if stack.len() == before {
+                        stats.dead_ends += 1;
+                    }
+
+                    stats.max_depth = stats.max_depth.max(stack.len());
                 }
                 None => {
-                    if current.is_complete() {
-                        return Ok(current);
+                    if current.is_valid() && current.is_complete() {
+                        observer.solution(&current);
+                        solutions.push(current);
+                    } else {
+                        stats.dead_ends += 1;
                     }
                 }
             }
         }
 
-        Err(())
+        stats.elapsed = start.elapsed();
+        Ok(SearchReport { solutions, stats })
+    }
+
+    fn check(&self, start: Instant, nodes: usize) -> Result<(), SearchError> {
+        if let Some(token) = &self.limits.cancellation {
+            if token.is_cancelled() {
+                return Err(SearchError::Cancelled);
+            }
+        }
+
+        if let Some(time) = self.limits.time {
+            if start.elapsed() >= time {
+                return Err(SearchError::TimeLimit);
+            }
+        }
+
+        if let Some(max_nodes) = self.limits.nodes {
+            if nodes >= max_nodes {
+                return Err(SearchError::NodeLimit);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Solver for Search<SudokuProblem> {
+    fn solutions(&self, limit: usize) -> Result<Vec<SudokuProblem>, SearchError> {
+        Ok(self.search(limit, &mut ())?.solutions)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::sized::SizedProblem;
     use std::str::FromStr;
 
+    #[derive(Default)]
+    struct Recorder {
+        pushes: usize,
+        pops: usize,
+        solutions: usize,
+        depths: Vec<usize>,
+        // The size of the stack, & the most it held.
+        size: usize,
+        max_size: usize,
+    }
+
+    impl Observer for Recorder {
+        fn push(&mut self, _problem: &SudokuProblem, depth: usize) {
+            self.pushes += 1;
+            self.depths.push(depth);
+            self.size += 1;
+            self.max_size = self.max_size.max(self.size);
+        }
+
+        fn pop(&mut self, _problem: &SudokuProblem, _depth: usize) {
+            self.pops += 1;
+            self.size -= 1;
+        }
+
+        fn solution(&mut self, _problem: &SudokuProblem) {
+            self.solutions += 1;
+        }
+    }
+
     #[test]
     fn search() {
         // Setup
@@ -65,4 +367,218 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn search_stats() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let unknowns = problem.unknowns();
+        let search = Search::new(problem);
+        let mut recorder = Recorder::default();
+
+        // Execute
+        let report = search.search(1, &mut recorder).unwrap();
+
+        // Verify
+        assert_eq!(report.solutions.len(), 1);
+        assert_eq!(report.stats.max_depth, recorder.max_size);
+        assert_eq!(report.stats.nodes, recorder.pops);
+        assert!(report.stats.nodes > unknowns);
+        assert!(report.stats.dead_ends > 0);
+        assert_eq!(recorder.solutions, 1);
+        assert!(recorder.pushes >= recorder.pops);
+        assert_eq!(recorder.depths.iter().max(), Some(&unknowns));
+    }
+
+    #[test]
+    fn search_stats_forced() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "379000014060010070080009005435007000090040020000800436900700080040080050850000249",
+        )
+        .unwrap();
+        let unknowns = problem.unknowns();
+        let search =
+            Search::new(problem).with_branching(Branching::MinimumRemainingValues(TieBreak::First));
+        let mut recorder = Recorder::default();
+
+        // Execute
+        let report = search.search(1, &mut recorder).unwrap();
+
+        // Verify
+        // Most of the cells chosen have a single candidate, so the stack stays far shorter than the values placed.
+        assert_eq!(report.stats.max_depth, recorder.max_size);
+        assert_eq!(recorder.depths.iter().max(), Some(&unknowns));
+        assert!(report.stats.max_depth < 5, "{}", report.stats);
+    }
+
+    #[test]
+    fn search_stats_solved() {
+        // A complete problem is a single node, with no dead ends.
+        let problem = SudokuProblem::from_str(
+            "379526814564318972281479365435267198698143527712895436923754681146982753857631249",
+        )
+        .unwrap();
+        let report = Search::new(problem).search(2, &mut ()).unwrap();
+        assert_eq!(report.solutions.len(), 1);
+        assert_eq!(report.stats.nodes, 1);
+        assert_eq!(report.stats.dead_ends, 0);
+        assert_eq!(report.stats.max_depth, 1);
+    }
+
+    #[test]
+    fn search_limits() {
+        // Unsolvable, but only at the last cell: r9c9 has no candidates (1-8 are in its row, 9 is in its column).
+        let problem = SudokuProblem::from_str(
+            "000000009000000000000000000000000000000000000000000000000000000000000000123456780",
+        )
+        .unwrap();
+
+        let search = Search::new(problem.clone()).with_limits(Limits {
+            nodes: Some(100),
+            ..Limits::default()
+        });
+        assert_matches!(search.run(), Err(SearchError::NodeLimit));
+        assert_eq!(search.solutions(1), Err(SearchError::NodeLimit));
+        assert_eq!(search.uniqueness(), Err(SearchError::NodeLimit));
+
+        let search = Search::new(problem.clone()).with_limits(Limits {
+            time: Some(Duration::from_millis(10)),
+            ..Limits::default()
+        });
+        assert_matches!(search.run(), Err(SearchError::TimeLimit));
+
+        let token = CancellationToken::new();
+        let search = Search::new(problem).with_limits(Limits {
+            cancellation: Some(token.clone()),
+            ..Limits::default()
+        });
+        let handle = std::thread::spawn(move || search.run());
+        token.cancel();
+        assert_matches!(handle.join().unwrap(), Err(SearchError::Cancelled));
+    }
+
+    #[test]
+    fn search_no_solution() {
+        let problem = SudokuProblem::from_str(
+            "012345678900000000000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        assert_matches!(Search::new(problem).run(), Err(SearchError::NoSolution));
+    }
+
+    #[test]
+    fn branching() {
+        // Setup
+        let problem = SudokuProblem::from_str(
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        )
+        .unwrap();
+        let expected = SudokuProblem::from_str(
+            "812753649943682175675491283154237896369845721287169534521974368438526917796318452",
+        )
+        .unwrap();
+
+        for branching in &Branching::all()[1..] {
+            // Execute
+            let report = Search::new(problem.clone())
+                .with_branching(*branching)
+                .search(2, &mut ())
+                .unwrap();
+
+            // Verify
+            assert_eq!(report.solutions, vec![expected.clone()], "{}", branching);
+        }
+    }
+
+    #[test]
+    fn branching_choose() {
+        // r1c1 & r9c9 both have a single candidate; r9c9 has more unknown peers.
+        let problem = SudokuProblem::from_str(
+            "023456781000000000000000000000000000200000000000000000000000000000000000345678120",
+        )
+        .unwrap();
+        let mrv = |tie_break| Branching::MinimumRemainingValues(tie_break).choose(&problem);
+
+        assert_eq!(Branching::FirstUnknown.choose(&problem), Some(0));
+        assert_eq!(mrv(TieBreak::First), Some(0));
+        assert_eq!(mrv(TieBreak::Last), Some(80));
+        assert_eq!(mrv(TieBreak::MostUnknownPeers), Some(80));
+    }
+
+    #[test]
+    fn search_sized() {
+        // Setup
+        let problem = SizedProblem::<2>::from_str("1... ..2. .3.. ...4").unwrap();
+
+        // Execute
+        let report = Search::new(problem).search(usize::MAX, &mut ()).unwrap();
+
+        // Verify
+        assert!(!report.solutions.is_empty());
+
+        for solution in report.solutions.iter() {
+            assert!(solution.is_valid());
+            assert!(solution.is_complete());
+            assert_eq!(solution.value(0, 0), 1);
+            assert_eq!(solution.value(3, 3), 4);
+        }
+    }
+
+    #[test]
+    fn search_sized_classic() {
+        // The sized model finds the same solution as the classic one.
+        let sdm =
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400";
+        let classic = Search::new(SudokuProblem::from_str(sdm).unwrap())
+            .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+            .run()
+            .unwrap();
+        let sized = Search::new(SizedProblem::<3>::from_str(sdm).unwrap())
+            .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+            .run()
+            .unwrap();
+        assert_eq!(format!("{:?}", sized), format!("{:?}", classic));
+    }
+
+    #[test]
+    fn search_hexadoku() {
+        // Setup
+        let problem = SizedProblem::<4>::from_str(
+            "
+            1... 5... 9... D...
+            .6.. .A.. .E.. .2..
+            ..B. ..F. ..3. ..7.
+            ...G ...4 ...8 ...C
+            2... 6... A... E...
+            .7.. .B.. .F.. .3..
+            ..C. ..G. ..4. ..8.
+            ...1 ...5 ...9 ...D
+            3... 7... B... F...
+            .8.. .C.. .G.. .4..
+            ..D. ..1. ..5. ..9.
+            ...2 ...6 ...A ...E
+            4... 8... C... G...
+            .9.. .D.. .1.. .5..
+            ..E. ..2. ..6. ..A.
+            ...3 ...7 ...B ...F
+            ",
+        )
+        .unwrap();
+
+        // Execute
+        let solution = Search::new(problem)
+            .with_branching(Branching::MinimumRemainingValues(TieBreak::First))
+            .run()
+            .unwrap();
+
+        // Verify
+        assert!(solution.is_valid());
+        assert!(solution.is_complete());
+        assert_eq!(solution.value(0, 0), 1);
+        assert_eq!(solution.value(15, 15), 15);
+    }
 }
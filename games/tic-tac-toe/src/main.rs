@@ -14,5 +14,5 @@ fn main() {
         .build();
     parser.parse();
 
-    println!("{}", player_1.to_string());
+    println!("{}", player_1);
 }